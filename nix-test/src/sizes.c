@@ -1,5 +1,10 @@
 #include "sys/socket.h"
 #include "sys/uio.h"
+#include "sys/statvfs.h"
+
+#ifdef __linux__
+#include "sys/statfs.h"
+#endif
 
 #define SIZE_OF_T(TYPE)                   \
     do {                                  \
@@ -23,5 +28,13 @@ size_of(const char* type) {
     // sys/uio
     SIZE_OF_S(iovec);
 
+    // sys/statvfs
+    SIZE_OF_S(statvfs);
+
+#ifdef __linux__
+    // sys/statfs
+    SIZE_OF_S(statfs);
+#endif
+
     return 0;
 }
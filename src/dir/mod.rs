@@ -0,0 +1,211 @@
+//! Directory iteration
+//!
+//! A `Dir` wraps a `DIR` stream. Entries are read with `readdir`, which on
+//! Linux is backed by `getdents64` and fetches many entries per system call.
+//!
+//! [Further reading](http://man7.org/linux/man-pages/man3/readdir.3.html)
+use {Error, Result, NixPath};
+use errno::Errno;
+use fcntl::{self, Fd, DirFd, OFlag, O_DIRECTORY};
+use libc::{c_long, c_char};
+use std::ffi::CStr;
+use sys::stat::{FileType, Mode};
+use unistd::close;
+
+pub mod walk;
+
+mod ffi {
+    use libc::{c_int, c_long};
+    pub use self::os::*;
+
+    pub enum DIR {}
+
+    extern {
+        #[cfg_attr(target_os = "macos", link_name = "fdopendir$INODE64")]
+        pub fn fdopendir(fd: c_int) -> *mut DIR;
+        #[cfg_attr(target_os = "macos", link_name = "readdir$INODE64")]
+        // The 64-bit entry layout below, also on 32-bit targets
+        #[cfg_attr(target_os = "linux", link_name = "readdir64")]
+        pub fn readdir(dirp: *mut DIR) -> *mut dirent;
+        pub fn closedir(dirp: *mut DIR) -> c_int;
+        pub fn dirfd(dirp: *mut DIR) -> c_int;
+        #[cfg_attr(target_os = "macos", link_name = "rewinddir$INODE64")]
+        pub fn rewinddir(dirp: *mut DIR);
+        #[cfg_attr(target_os = "macos", link_name = "telldir$INODE64")]
+        pub fn telldir(dirp: *mut DIR) -> c_long;
+        #[cfg_attr(target_os = "macos", link_name = "seekdir$INODE64")]
+        pub fn seekdir(dirp: *mut DIR, loc: c_long);
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    mod os {
+        use libc::c_char;
+
+        #[repr(C)]
+        pub struct dirent {
+            pub d_ino: u64,
+            pub d_off: i64,
+            pub d_reclen: u16,
+            pub d_type: u8,
+            pub d_name: [c_char; 256],
+        }
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    mod os {
+        use libc::c_char;
+
+        #[repr(C)]
+        pub struct dirent {
+            pub d_ino: u64,
+            pub d_seekoff: u64,
+            pub d_reclen: u16,
+            pub d_namlen: u16,
+            pub d_type: u8,
+            pub d_name: [c_char; 1024],
+        }
+    }
+}
+
+/// An open directory stream. The underlying descriptor is closed when the
+/// `Dir` is dropped.
+pub struct Dir {
+    dirp: *mut ffi::DIR,
+}
+
+unsafe impl Send for Dir {}
+
+impl Dir {
+    /// Open the directory at `path`. `O_DIRECTORY` is always added to `oflag`.
+    pub fn open<P: ?Sized + NixPath>(path: &P, oflag: OFlag, mode: Mode) -> Result<Dir> {
+        let fd = try!(fcntl::open(path, oflag | O_DIRECTORY, mode));
+        Dir::from_fd(fd)
+    }
+
+    /// Open the directory at `path` relative to `dirfd`. `O_DIRECTORY` is
+    /// always added to `oflag`.
+    pub fn openat<P: ?Sized + NixPath>(dirfd: DirFd, path: &P, oflag: OFlag, mode: Mode) -> Result<Dir> {
+        let fd = try!(fcntl::openat(dirfd, path, oflag | O_DIRECTORY, mode));
+        Dir::from_fd(fd)
+    }
+
+    /// Wrap an open directory descriptor using `fdopendir`. The `Dir` takes
+    /// ownership of `fd`, which is closed even if this fails.
+    pub fn from_fd(fd: Fd) -> Result<Dir> {
+        let dirp = unsafe { ffi::fdopendir(fd) };
+
+        if dirp.is_null() {
+            let err = Error::Sys(Errno::last());
+            let _ = close(fd);
+            return Err(err);
+        }
+
+        Ok(Dir { dirp: dirp })
+    }
+
+    /// Returns the descriptor of the directory, for use with the `*at` calls.
+    /// It remains owned by the `Dir`.
+    pub fn as_raw_fd(&self) -> Fd {
+        unsafe { ffi::dirfd(self.dirp) }
+    }
+
+    /// Returns an iterator over the remaining entries, including `.` and
+    /// `..`.
+    pub fn iter(&mut self) -> Iter {
+        Iter { dir: self }
+    }
+
+    /// Reset the stream to the beginning of the directory.
+    pub fn rewind(&mut self) {
+        unsafe { ffi::rewinddir(self.dirp) }
+    }
+
+    /// Returns the current position in the stream, to be passed to `seek`.
+    pub fn tell(&self) -> Result<c_long> {
+        let res = unsafe { ffi::telldir(self.dirp) };
+
+        if res < 0 {
+            return Err(Error::Sys(Errno::last()));
+        }
+
+        Ok(res)
+    }
+
+    /// Return to a position previously returned by `tell`.
+    pub fn seek(&mut self, loc: c_long) {
+        unsafe { ffi::seekdir(self.dirp, loc) }
+    }
+}
+
+impl Drop for Dir {
+    fn drop(&mut self) {
+        unsafe { ffi::closedir(self.dirp) };
+    }
+}
+
+/// Iterator over the entries of a `Dir`.
+pub struct Iter<'a> {
+    dir: &'a mut Dir,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Result<Entry>;
+
+    fn next(&mut self) -> Option<Result<Entry>> {
+        unsafe {
+            // readdir only reports errors through errno
+            Errno::clear();
+            let ent = ffi::readdir(self.dir.dirp);
+
+            if ent.is_null() {
+                return match Errno::last() {
+                    Errno::UnknownErrno => None,
+                    err => Some(Err(Error::Sys(err))),
+                };
+            }
+
+            let name = CStr::from_ptr((*ent).d_name.as_ptr() as *const c_char);
+
+            Some(Ok(Entry {
+                ino: (*ent).d_ino,
+                d_type: (*ent).d_type,
+                name: name.to_bytes().to_vec(),
+            }))
+        }
+    }
+}
+
+/// A directory entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    ino: u64,
+    d_type: u8,
+    name: Vec<u8>,
+}
+
+impl Entry {
+    /// Returns the inode number of the entry
+    pub fn ino(&self) -> u64 {
+        self.ino
+    }
+
+    /// Returns the raw bytes of the entry's name, without a trailing NUL
+    pub fn file_name(&self) -> &[u8] {
+        &self.name
+    }
+
+    /// Returns the type of the entry, or `None` if the filesystem does not
+    /// report it (`DT_UNKNOWN`); `sys::stat::fstatat` can be used instead.
+    pub fn file_type(&self) -> Option<FileType> {
+        match self.d_type {
+            1 => Some(FileType::Fifo),
+            2 => Some(FileType::CharDevice),
+            4 => Some(FileType::Directory),
+            6 => Some(FileType::BlockDevice),
+            8 => Some(FileType::Regular),
+            10 => Some(FileType::Symlink),
+            12 => Some(FileType::Socket),
+            _ => None,
+        }
+    }
+}
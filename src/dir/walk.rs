@@ -0,0 +1,202 @@
+//! Descriptor-based directory tree walking
+//!
+//! Every directory is opened relative to its parent's descriptor with
+//! `O_NOFOLLOW | O_DIRECTORY`, so replacing a directory with a symbolic link
+//! while the walk is in progress cannot redirect it outside the tree.
+//! Symbolic links are reported, never followed.
+use {Error, Result, NixPath};
+use dir::Dir;
+use errno::Errno;
+use fcntl::{openat, Fd, DirFd, AtFlags, AT_REMOVEDIR};
+use fcntl::{O_RDONLY, O_DIRECTORY, O_NOFOLLOW, O_CLOEXEC};
+use libc::dev_t;
+use std::os::unix::ffi::OsStrExt;
+use sys::stat::{fstat, FileStat, FileType, Mode};
+use unistd::{close, unlinkat};
+
+/// Why an entry is being visited.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// A directory, before its contents. Only reported with `pre_order`.
+    PreDir,
+    /// A directory, after its contents. Only reported with `post_order`.
+    PostDir,
+    /// Anything that is not a directory, including symbolic links to
+    /// directories.
+    File,
+}
+
+/// Returned by the visitor to control the walk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    Continue,
+    /// When returned for `Event::PreDir`, neither the directory's contents
+    /// nor its `Event::PostDir` are visited. Ignored for other events.
+    SkipDir,
+}
+
+/// Options controlling a walk.
+#[derive(Clone, Copy, Debug)]
+pub struct WalkOpts {
+    /// Report directories before their contents
+    pub pre_order: bool,
+    /// Report directories after their contents
+    pub post_order: bool,
+    /// Do not descend into directories on a different filesystem than the
+    /// root. Such directories are still reported.
+    pub same_fs: bool,
+    /// Do not descend into directories deeper than this. The root has depth 0.
+    pub max_depth: Option<usize>,
+}
+
+impl Default for WalkOpts {
+    fn default() -> WalkOpts {
+        WalkOpts {
+            pre_order: true,
+            post_order: false,
+            same_fs: false,
+            max_depth: None,
+        }
+    }
+}
+
+/// An entry reported to the visitor.
+pub struct Visit<'a> {
+    pub event: Event,
+    /// The directory containing the entry. Together with `name` this can be
+    /// passed to the `*at` calls.
+    pub dirfd: DirFd,
+    /// The entry's name within `dirfd`; the root is reported with the path
+    /// it was walked from.
+    pub name: &'a [u8],
+    pub depth: usize,
+    /// For directory events, the open descriptor of the directory itself
+    pub fd: Option<Fd>,
+    /// For directory events, the status of the directory itself
+    pub stat: Option<&'a FileStat>,
+    /// For `Event::File`, the type reported by the directory entry, if any
+    pub file_type: Option<FileType>,
+}
+
+/// Walk the tree rooted at `path`, relative to `dirfd`, calling `f` for each
+/// entry. The root must be a directory and not a symbolic link.
+///
+/// The walk stops at the first error returned by `f` or by the system.
+pub fn walk_at<P, F>(dirfd: DirFd, path: &P, opts: &WalkOpts, mut f: F) -> Result<()>
+        where P: ?Sized + NixPath,
+              F: FnMut(&Visit) -> Result<Control> {
+    let name = try!(path.with_nix_path(|osstr| osstr.as_bytes().to_vec()));
+    let fd = try!(open_dir(dirfd, path));
+    let st = match fstat(fd) {
+        Ok(st) => st,
+        Err(e) => {
+            let _ = close(fd);
+            return Err(e);
+        }
+    };
+
+    walk_dir(dirfd, &name, fd, &st, 0, st.st_dev, opts, &mut f)
+}
+
+/// Remove the tree rooted at `path`, relative to `dirfd`, including `path`
+/// itself. Symbolic links are removed rather than followed, and the walk
+/// does not cross into other filesystems; mount points inside the tree make
+/// it fail with `EBUSY`.
+pub fn remove_dir_all_at<P: ?Sized + NixPath>(dirfd: DirFd, path: &P) -> Result<()> {
+    let opts = WalkOpts {
+        pre_order: false,
+        post_order: true,
+        same_fs: true,
+        max_depth: None,
+    };
+
+    walk_at(dirfd, path, &opts, |visit| {
+        let flags = match visit.event {
+            Event::File => AtFlags::empty(),
+            _ => AT_REMOVEDIR,
+        };
+
+        try!(unlinkat(visit.dirfd, visit.name, flags));
+        Ok(Control::Continue)
+    })
+}
+
+fn open_dir<P: ?Sized + NixPath>(dirfd: DirFd, path: &P) -> Result<Fd> {
+    openat(dirfd, path, O_RDONLY | O_DIRECTORY | O_NOFOLLOW | O_CLOEXEC, Mode::empty())
+}
+
+// Takes ownership of `fd`
+fn walk_dir<F>(parent: DirFd, name: &[u8], fd: Fd, st: &FileStat, depth: usize,
+               root_dev: dev_t, opts: &WalkOpts, f: &mut F) -> Result<()>
+        where F: FnMut(&Visit) -> Result<Control> {
+    let mut dir = try!(Dir::from_fd(fd));
+
+    let visit = |event| Visit {
+        event: event,
+        dirfd: parent,
+        name: name,
+        depth: depth,
+        fd: Some(fd),
+        stat: Some(st),
+        file_type: Some(FileType::Directory),
+    };
+
+    if opts.pre_order {
+        if try!(f(&visit(Event::PreDir))) == Control::SkipDir {
+            return Ok(());
+        }
+    }
+
+    let descend = opts.max_depth.map_or(true, |max| depth < max) &&
+        (!opts.same_fs || st.st_dev == root_dev);
+
+    if descend {
+        for entry in dir.iter() {
+            let entry = try!(entry);
+            let child = entry.file_name();
+
+            if child == b"." || child == b".." {
+                continue;
+            }
+
+            // Only entries that may be directories need to be opened; the
+            // open itself decides, so a concurrent swap for a symbolic link
+            // fails with ELOOP instead of being followed.
+            if entry.file_type().map_or(true, |t| t == FileType::Directory) {
+                match open_dir(DirFd::Fd(fd), child) {
+                    Ok(child_fd) => {
+                        let child_st = match fstat(child_fd) {
+                            Ok(st) => st,
+                            Err(e) => {
+                                let _ = close(child_fd);
+                                return Err(e);
+                            }
+                        };
+
+                        try!(walk_dir(DirFd::Fd(fd), child, child_fd, &child_st,
+                                      depth + 1, root_dev, opts, f));
+                        continue;
+                    }
+                    Err(Error::Sys(Errno::ENOTDIR)) | Err(Error::Sys(Errno::ELOOP)) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+
+            try!(f(&Visit {
+                event: Event::File,
+                dirfd: DirFd::Fd(fd),
+                name: child,
+                depth: depth + 1,
+                fd: None,
+                stat: None,
+                file_type: entry.file_type(),
+            }));
+        }
+    }
+
+    if opts.post_order {
+        try!(f(&visit(Event::PostDir)));
+    }
+
+    Ok(())
+}
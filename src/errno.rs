@@ -5,45 +5,43 @@ pub use self::consts::Errno::*;
 
 /// Returns the platform-specific value of errno
 pub fn errno() -> i32 {
-    #[cfg(any(target_os = "macos",
-              target_os = "ios",
-              target_os = "freebsd"))]
-    unsafe fn errno_location() -> *const c_int {
-        extern { fn __error() -> *const c_int; }
-        __error()
+    unsafe {
+        (*errno_location()) as i32
     }
+}
 
-    #[cfg(target_os = "bitrig")]
-    fn errno_location() -> *const c_int {
-        extern {
-            fn __errno() -> *const c_int;
-        }
-        unsafe {
-            __errno()
-        }
-    }
+#[cfg(any(target_os = "macos",
+          target_os = "ios",
+          target_os = "freebsd"))]
+unsafe fn errno_location() -> *mut c_int {
+    extern { fn __error() -> *mut c_int; }
+    __error()
+}
 
-    #[cfg(target_os = "dragonfly")]
-    unsafe fn errno_location() -> *const c_int {
-        extern { fn __dfly_error() -> *const c_int; }
-        __dfly_error()
+#[cfg(target_os = "bitrig")]
+unsafe fn errno_location() -> *mut c_int {
+    extern {
+        fn __errno() -> *mut c_int;
     }
+    __errno()
+}
 
-    #[cfg(target_os = "openbsd")]
-    unsafe fn errno_location() -> *const c_int {
-        extern { fn __errno() -> *const c_int; }
-        __errno()
-    }
+#[cfg(target_os = "dragonfly")]
+unsafe fn errno_location() -> *mut c_int {
+    extern { fn __dfly_error() -> *mut c_int; }
+    __dfly_error()
+}
 
-    #[cfg(any(target_os = "linux", target_os = "android"))]
-    unsafe fn errno_location() -> *const c_int {
-        extern { fn __errno_location() -> *const c_int; }
-        __errno_location()
-    }
+#[cfg(target_os = "openbsd")]
+unsafe fn errno_location() -> *mut c_int {
+    extern { fn __errno() -> *mut c_int; }
+    __errno()
+}
 
-    unsafe {
-        (*errno_location()) as i32
-    }
+#[cfg(any(target_os = "linux", target_os = "android"))]
+unsafe fn errno_location() -> *mut c_int {
+    extern { fn __errno_location() -> *mut c_int; }
+    __errno_location()
 }
 
 macro_rules! impl_errno {
@@ -60,6 +58,12 @@ macro_rules! impl_errno {
             pub fn from_i32(err: i32) -> Errno {
                 from_i32(err)
             }
+
+            /// Sets errno to zero, for calls that only report failure
+            /// through errno, such as `readdir`.
+            pub fn clear() {
+                super::clear()
+            }
         }
     }
 }
@@ -68,6 +72,12 @@ fn last() -> Errno {
     Errno::from_i32(errno())
 }
 
+fn clear() {
+    unsafe {
+        *errno_location() = 0;
+    }
+}
+
 fn desc(errno: Errno) -> &'static str {
     match errno {
         UnknownErrno    => "Unknown errno",
@@ -1,7 +1,13 @@
-use {Error, Result, NixPath, AsExtStr};
-use errno::Errno;
-use libc::mode_t;
+use {Error, Result, NixPath, AsExtStr, from_ffi};
+use errno::{errno, Errno};
+use libc::{c_int, mode_t, off_t, pid_t};
+use std::mem;
+use sys::signal::SigNum;
 use sys::stat::Mode;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use sys::uio::IoVec;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use unistd::{close, linkat, unlinkat};
 
 pub use self::consts::*;
 pub use self::ffi::flock;
@@ -9,11 +15,92 @@ pub use self::ffi::flock;
 // Re-export Fd defined in std
 pub type Fd = ::std::os::unix::io::RawFd;
 
-#[allow(dead_code)]
+#[allow(dead_code, improper_ctypes)]
 mod ffi {
+    use libc::{c_char, c_int, c_short, c_uint, mode_t, off_t, size_t, ssize_t};
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    use sys::uio::IoVec;
     pub use libc::{open, fcntl};
     pub use self::os::*;
 
+    extern {
+        // open a file relative to a directory file descriptor
+        // doc: http://man7.org/linux/man-pages/man2/openat.2.html
+        pub fn openat(dirfd: c_int, pathname: *const c_char, flags: c_int, mode: mode_t) -> c_int;
+
+        // change the name of a file relative to directory file descriptors
+        // doc: http://man7.org/linux/man-pages/man2/renameat.2.html
+        pub fn renameat(olddirfd: c_int, oldpath: *const c_char, newdirfd: c_int, newpath: *const c_char) -> c_int;
+
+        // apply or remove an advisory lock on an open file
+        // doc: http://man7.org/linux/man-pages/man2/flock.2.html
+        // (renamed so as not to clash with the re-exported struct flock)
+        #[link_name = "flock"]
+        pub fn flock_fd(fd: c_int, operation: c_int) -> c_int;
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    extern {
+        // manipulate the allocated space of a file
+        // doc: http://man7.org/linux/man-pages/man2/fallocate.2.html
+        pub fn fallocate(fd: c_int, mode: c_int, offset: off_t, len: off_t) -> c_int;
+        pub fn posix_fallocate(fd: c_int, offset: off_t, len: off_t) -> c_int;
+
+        // announce an access pattern for file data
+        // doc: http://man7.org/linux/man-pages/man2/posix_fadvise.2.html
+        pub fn posix_fadvise(fd: c_int, offset: off_t, len: off_t, advice: c_int) -> c_int;
+
+        // initiate file readahead into page cache
+        // doc: http://man7.org/linux/man-pages/man2/readahead.2.html
+        pub fn readahead(fd: c_int, offset: i64, count: size_t) -> ssize_t;
+
+        // sync a file segment with disk
+        // doc: http://man7.org/linux/man-pages/man2/sync_file_range.2.html
+        pub fn sync_file_range(fd: c_int, offset: i64, nbytes: i64, flags: c_uint) -> c_int;
+
+        // move data between a pipe and another file descriptor
+        // doc: http://man7.org/linux/man-pages/man2/splice.2.html
+        pub fn splice(fd_in: c_int, off_in: *mut i64, fd_out: c_int, off_out: *mut i64,
+                      len: size_t, flags: c_uint) -> ssize_t;
+
+        // duplicate pipe content
+        // doc: http://man7.org/linux/man-pages/man2/tee.2.html
+        pub fn tee(fd_in: c_int, fd_out: c_int, len: size_t, flags: c_uint) -> ssize_t;
+
+        // splice user pages into a pipe
+        // doc: http://man7.org/linux/man-pages/man2/vmsplice.2.html
+        pub fn vmsplice(fd: c_int, iov: *const IoVec<&[u8]>, nr_segs: size_t, flags: c_uint) -> ssize_t;
+
+        // copy a range of data from one file to another
+        // doc: http://man7.org/linux/man-pages/man2/copy_file_range.2.html
+        pub fn copy_file_range(fd_in: c_int, off_in: *mut i64, fd_out: c_int, off_out: *mut i64,
+                               len: size_t, flags: c_uint) -> ssize_t;
+
+        // obtain handle for a pathname and open file via a handle
+        // doc: http://man7.org/linux/man-pages/man2/open_by_handle_at.2.html
+        pub fn name_to_handle_at(dirfd: c_int, pathname: *const c_char, handle: *mut file_handle,
+                                 mount_id: *mut c_int, flags: c_int) -> c_int;
+        pub fn open_by_handle_at(mount_fd: c_int, handle: *mut file_handle, flags: c_int) -> c_int;
+    }
+
+    // Followed by handle_bytes bytes of f_handle
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[repr(C)]
+    pub struct file_handle {
+        pub handle_bytes: c_uint,
+        pub handle_type: c_int,
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub const MAX_HANDLE_SZ: usize = 128;
+
+    pub const LOCK_SH: c_int = 1;
+    pub const LOCK_EX: c_int = 2;
+    pub const LOCK_NB: c_int = 4;
+    pub const LOCK_UN: c_int = 8;
+
+    pub const SEEK_SET: c_short = 0;
+
     #[cfg(any(target_os = "linux", target_os = "android"))]
     mod os {
         use libc::{c_int, c_short, off_t, pid_t};
@@ -40,6 +127,37 @@ mod ffi {
         pub const F_SETLK:         c_int = 6;
         pub const F_SETLKW:        c_int = 7;
         pub const F_GETLK:         c_int = 5;
+        // 32-bit targets need fcntl64 and a 64-bit flock for these
+        #[cfg(target_pointer_width = "64")]
+        pub const F_OFD_GETLK:     c_int = 36;
+        #[cfg(target_pointer_width = "64")]
+        pub const F_OFD_SETLK:     c_int = 37;
+        #[cfg(target_pointer_width = "64")]
+        pub const F_OFD_SETLKW:    c_int = 38;
+        pub const F_SETOWN:        c_int = 8;
+        pub const F_GETOWN:        c_int = 9;
+        pub const F_SETSIG:        c_int = 10;
+        pub const F_GETSIG:        c_int = 11;
+        pub const F_SETOWN_EX:     c_int = 15;
+        pub const F_GETOWN_EX:     c_int = 16;
+        pub const F_SETPIPE_SZ:    c_int = 1031;
+        pub const F_GETPIPE_SZ:    c_int = 1032;
+        pub const F_ADD_SEALS:     c_int = 1033;
+        pub const F_GET_SEALS:     c_int = 1034;
+
+        pub const F_OWNER_TID:  c_int = 0;
+        pub const F_OWNER_PID:  c_int = 1;
+        pub const F_OWNER_PGRP: c_int = 2;
+
+        #[repr(C)]
+        pub struct f_owner_ex {
+            pub type_: c_int,
+            pub pid: pid_t,
+        }
+
+        pub const F_RDLCK: c_short = 0;
+        pub const F_WRLCK: c_short = 1;
+        pub const F_UNLCK: c_short = 2;
     }
 
     #[cfg(any(target_os = "macos", target_os = "ios"))]
@@ -68,6 +186,12 @@ mod ffi {
         pub const F_SETLK:         c_int = 8;
         pub const F_SETLKW:        c_int = 9;
         pub const F_GETLK:         c_int = 7;
+        pub const F_GETOWN:        c_int = 5;
+        pub const F_SETOWN:        c_int = 6;
+
+        pub const F_RDLCK: c_short = 1;
+        pub const F_UNLCK: c_short = 2;
+        pub const F_WRLCK: c_short = 3;
     }
 }
 
@@ -83,6 +207,252 @@ pub fn open<P: ?Sized + NixPath>(path: &P, oflag: OFlag, mode: Mode) -> Result<F
     Ok(fd)
 }
 
+/// The directory relative to which the `*at` family of calls resolves
+/// relative paths. Absolute paths ignore it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DirFd {
+    /// The current working directory (`AT_FDCWD`)
+    Cwd,
+    /// An open directory
+    Fd(Fd),
+}
+
+impl DirFd {
+    /// Returns the descriptor to pass to the system call
+    pub fn as_raw(&self) -> Fd {
+        match *self {
+            DirFd::Cwd => AT_FDCWD,
+            DirFd::Fd(fd) => fd,
+        }
+    }
+}
+
+pub fn openat<P: ?Sized + NixPath>(dirfd: DirFd, path: &P, oflag: OFlag, mode: Mode) -> Result<Fd> {
+    let fd = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::openat(dirfd.as_raw(), osstr.as_ext_str(), oflag.bits(), mode.bits() as mode_t) }
+    }));
+
+    if fd < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(fd)
+}
+
+/// How `openat2` should open a file and resolve its path.
+///
+/// [Further reading](http://man7.org/linux/man-pages/man2/openat2.2.html)
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpenHow {
+    flags: u64,
+    mode: u64,
+    resolve: u64,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl OpenHow {
+    /// Unlike `openat`, the kernel rejects a non-empty `mode` unless `oflag`
+    /// contains `O_CREAT` or `O_TMPFILE`.
+    pub fn new(oflag: OFlag, mode: Mode, resolve: ResolveFlag) -> OpenHow {
+        OpenHow {
+            flags: oflag.bits() as u64,
+            mode: mode.bits() as u64,
+            resolve: resolve.bits(),
+        }
+    }
+
+    pub fn oflag(&self) -> OFlag {
+        OFlag::from_bits_truncate(self.flags as c_int)
+    }
+
+    pub fn mode(&self) -> Mode {
+        Mode::from_bits_truncate(self.mode as mode_t)
+    }
+
+    pub fn resolve(&self) -> ResolveFlag {
+        ResolveFlag::from_bits_truncate(self.resolve)
+    }
+}
+
+/// Like `openat`, with additional restrictions on how `path` is resolved.
+///
+/// Fails with `ENOSYS` on kernels older than Linux 5.6.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn openat2<P: ?Sized + NixPath>(dirfd: DirFd, path: &P, how: &OpenHow) -> Result<Fd> {
+    use libc::size_t;
+    use std::mem;
+    use sys::syscall::{syscall, SYSOPENAT2};
+
+    let fd = try!(path.with_nix_path(|osstr| {
+        unsafe {
+            syscall(SYSOPENAT2, dirfd.as_raw(), osstr.as_ext_str(),
+                    how as *const OpenHow, mem::size_of::<OpenHow>() as size_t)
+        }
+    }));
+
+    if fd < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(fd)
+}
+
+/// A persistent identifier for a file, returned by `name_to_handle_at`. It
+/// stays valid across renames, and across reboots for filesystems that
+/// support it, for as long as the file exists.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FileHandle {
+    handle_type: c_int,
+    handle: Vec<u8>,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl FileHandle {
+    /// Build a handle from its parts, e.g. as reported by fanotify
+    pub fn new(handle_type: c_int, handle: Vec<u8>) -> FileHandle {
+        FileHandle { handle_type: handle_type, handle: handle }
+    }
+
+    /// The filesystem-specific type of the handle
+    pub fn handle_type(&self) -> c_int {
+        self.handle_type
+    }
+
+    /// The opaque handle data
+    pub fn handle(&self) -> &[u8] {
+        &self.handle
+    }
+
+    /// Serialise the handle for storage, as its type in four little-endian
+    /// bytes followed by the handle data.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let t = self.handle_type as u32;
+        let mut bytes = vec![t as u8, (t >> 8) as u8, (t >> 16) as u8, (t >> 24) as u8];
+        bytes.extend(self.handle.iter().cloned());
+        bytes
+    }
+
+    /// Restore a handle serialised by `to_bytes`. Fails with `EINVAL` if
+    /// `bytes` cannot be a handle.
+    pub fn from_bytes(bytes: &[u8]) -> Result<FileHandle> {
+        if bytes.len() < 4 || bytes.len() - 4 > ffi::MAX_HANDLE_SZ {
+            return Err(Error::invalid_argument());
+        }
+
+        let t = bytes[0] as u32 | (bytes[1] as u32) << 8 | (bytes[2] as u32) << 16 | (bytes[3] as u32) << 24;
+        Ok(FileHandle::new(t as c_int, bytes[4..].to_vec()))
+    }
+}
+
+// A struct file_handle with room for `len` bytes of handle, as u32s to keep
+// the header aligned
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn file_handle_buf(len: usize) -> Vec<u32> {
+    let header = mem::size_of::<ffi::file_handle>();
+    vec![0; (header + len + 3) / 4]
+}
+
+/// Get a handle for the file at `path`, along with the ID of the mount it is
+/// on, as found in `/proc/self/mountinfo`. With `AT_EMPTY_PATH` and an empty
+/// `path`, the handle is for `dirfd` itself.
+///
+/// Fails with `EOPNOTSUPP` if the filesystem does not support handles.
+///
+/// [Further reading](http://man7.org/linux/man-pages/man2/open_by_handle_at.2.html)
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn name_to_handle_at<P: ?Sized + NixPath>(dirfd: DirFd, path: &P, flags: AtFlags) -> Result<(FileHandle, c_int)> {
+    use std::slice;
+
+    // The kernel rejects larger buffers, so no handle is ever bigger and
+    // EOVERFLOW is an error like any other
+    let mut buf = file_handle_buf(ffi::MAX_HANDLE_SZ);
+    let handle = buf.as_mut_ptr() as *mut ffi::file_handle;
+    let mut mount_id = 0;
+
+    let res = try!(path.with_nix_path(|osstr| unsafe {
+        (*handle).handle_bytes = ffi::MAX_HANDLE_SZ as ::libc::c_uint;
+        ffi::name_to_handle_at(dirfd.as_raw(), osstr.as_ext_str(), handle, &mut mount_id, flags.bits())
+    }));
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    let handle = unsafe {
+        let data = (handle as *const u8).offset(mem::size_of::<ffi::file_handle>() as isize);
+        FileHandle::new((*handle).handle_type,
+                        slice::from_raw_parts(data, (*handle).handle_bytes as usize).to_vec())
+    };
+
+    Ok((handle, mount_id))
+}
+
+/// Open the file identified by `handle`. `mount_fd` is any descriptor on the
+/// same mount as the file.
+///
+/// Requires `CAP_DAC_READ_SEARCH`, and fails with `ESTALE` if the file no
+/// longer exists.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn open_by_handle_at(mount_fd: Fd, handle: &FileHandle, flags: OFlag) -> Result<Fd> {
+    use std::ptr;
+
+    let mut buf = file_handle_buf(handle.handle.len());
+    let raw = buf.as_mut_ptr() as *mut ffi::file_handle;
+
+    let fd = unsafe {
+        (*raw).handle_bytes = handle.handle.len() as ::libc::c_uint;
+        (*raw).handle_type = handle.handle_type;
+        ptr::copy_nonoverlapping(handle.handle.as_ptr(),
+                                 (raw as *mut u8).offset(mem::size_of::<ffi::file_handle>() as isize),
+                                 handle.handle.len());
+        ffi::open_by_handle_at(mount_fd, raw, flags.bits())
+    };
+
+    if fd < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(fd)
+}
+
+pub fn renameat<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(
+        old_dirfd: DirFd, old_path: &P1, new_dirfd: DirFd, new_path: &P2) -> Result<()> {
+    let res = try!(try!(old_path.with_nix_path(|old| {
+        new_path.with_nix_path(|new| {
+            unsafe {
+                ffi::renameat(old_dirfd.as_raw(), old.as_ext_str(), new_dirfd.as_raw(), new.as_ext_str())
+            }
+        })
+    })));
+
+    from_ffi(res)
+}
+
+/// Like `renameat`, with `flags` controlling whether an existing `new_path`
+/// may be replaced or is atomically exchanged with `old_path`.
+///
+/// [Further reading](http://man7.org/linux/man-pages/man2/renameat2.2.html)
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn renameat2<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(
+        old_dirfd: DirFd, old_path: &P1, new_dirfd: DirFd, new_path: &P2, flags: RenameFlags) -> Result<()> {
+    use libc::c_uint;
+    use sys::syscall::{syscall, SYSRENAMEAT2};
+
+    let res = try!(try!(old_path.with_nix_path(|old| {
+        new_path.with_nix_path(|new| {
+            unsafe {
+                syscall(SYSRENAMEAT2, old_dirfd.as_raw(), old.as_ext_str(),
+                        new_dirfd.as_raw(), new.as_ext_str(), flags.bits() as c_uint)
+            }
+        })
+    })));
+
+    from_ffi(res)
+}
+
 pub enum FcntlArg<'a> {
     F_DUPFD(Fd),
     F_DUPFD_CLOEXEC(Fd),
@@ -93,25 +463,109 @@ pub enum FcntlArg<'a> {
     F_SETLK(&'a flock),
     F_SETLKW(&'a flock),
     F_GETLK(&'a mut flock),
-    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[cfg(all(any(target_os = "linux", target_os = "android"), target_pointer_width = "64"))]
     F_OFD_SETLK(&'a flock),
-    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[cfg(all(any(target_os = "linux", target_os = "android"), target_pointer_width = "64"))]
     F_OFD_SETLKW(&'a flock),
+    #[cfg(all(any(target_os = "linux", target_os = "android"), target_pointer_width = "64"))]
+    F_OFD_GETLK(&'a mut flock),
+    F_SETOWN(pid_t), // negative for a process group
+    F_GETOWN,
     #[cfg(any(target_os = "linux", target_os = "android"))]
-    F_OFD_GETLK(&'a mut flock)
+    F_SETOWN_EX(Owner),
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    F_GETOWN_EX(&'a mut Owner),
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    F_SETSIG(SigNum), // 0 for SIGIO
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    F_GETSIG,
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    F_ADD_SEALS(SealFlag),
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    F_GET_SEALS, // see SealFlag::from_bits_truncate
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    F_SETPIPE_SZ(c_int),
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    F_GETPIPE_SZ,
 
     // TODO: Rest of flags
 }
 
-// TODO: Figure out how to handle value fcntl returns
-pub fn fcntl(fd: Fd, arg: FcntlArg) -> Result<()> {
+/// The recipient of the signals sent for a descriptor with `O_ASYNC` set.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Owner {
+    Thread(pid_t),
+    Process(pid_t),
+    ProcessGroup(pid_t),
+}
+
+/// Perform `arg` on `fd`. Returns the requested value for `F_DUPFD`,
+/// `F_DUPFD_CLOEXEC`, `F_GETFD`, `F_GETFL`, `F_GETOWN`, `F_GETSIG`,
+/// `F_GET_SEALS`, `F_SETPIPE_SZ` and `F_GETPIPE_SZ`, and zero otherwise.
+///
+/// `F_GETOWN` returns a process group owner as its negated id.
+pub fn fcntl(fd: Fd, arg: FcntlArg) -> Result<c_int> {
     use self::FcntlArg::*;
 
     let res = unsafe {
         match arg {
+            F_DUPFD(min) => ffi::fcntl(fd, ffi::F_DUPFD, min),
+            F_DUPFD_CLOEXEC(min) => ffi::fcntl(fd, ffi::F_DUPFD_CLOEXEC, min),
+            F_GETFD => ffi::fcntl(fd, ffi::F_GETFD),
             F_SETFD(flag) => ffi::fcntl(fd, ffi::F_SETFD, flag.bits()),
+            F_GETFL => ffi::fcntl(fd, ffi::F_GETFL),
             F_SETFL(flag) => ffi::fcntl(fd, ffi::F_SETFL, flag.bits()),
-            _ => unimplemented!()
+            F_SETLK(lock) => ffi::fcntl(fd, ffi::F_SETLK, lock as *const flock),
+            F_SETLKW(lock) => ffi::fcntl(fd, ffi::F_SETLKW, lock as *const flock),
+            F_GETLK(lock) => ffi::fcntl(fd, ffi::F_GETLK, lock as *mut flock),
+            #[cfg(all(any(target_os = "linux", target_os = "android"), target_pointer_width = "64"))]
+            F_OFD_SETLK(lock) => ffi::fcntl(fd, ffi::F_OFD_SETLK, lock as *const flock),
+            #[cfg(all(any(target_os = "linux", target_os = "android"), target_pointer_width = "64"))]
+            F_OFD_SETLKW(lock) => ffi::fcntl(fd, ffi::F_OFD_SETLKW, lock as *const flock),
+            #[cfg(all(any(target_os = "linux", target_os = "android"), target_pointer_width = "64"))]
+            F_OFD_GETLK(lock) => ffi::fcntl(fd, ffi::F_OFD_GETLK, lock as *mut flock),
+            F_SETOWN(pid) => ffi::fcntl(fd, ffi::F_SETOWN, pid),
+            F_GETOWN => {
+                // A process group owner is returned as its negated id, which
+                // may be -1, so only errno tells failure apart
+                Errno::clear();
+                let res = ffi::fcntl(fd, ffi::F_GETOWN);
+
+                if res == -1 && errno() != 0 {
+                    return Err(Error::Sys(Errno::last()));
+                }
+
+                return Ok(res);
+            }
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            F_SETOWN_EX(owner) => {
+                let owner = owner.to_raw();
+                ffi::fcntl(fd, ffi::F_SETOWN_EX, &owner as *const ffi::f_owner_ex)
+            }
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            F_GETOWN_EX(owner) => {
+                let mut raw = ffi::f_owner_ex { type_: 0, pid: 0 };
+                let res = ffi::fcntl(fd, ffi::F_GETOWN_EX, &mut raw as *mut ffi::f_owner_ex);
+
+                if res == 0 {
+                    *owner = Owner::from_raw(&raw);
+                }
+
+                res
+            }
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            F_SETSIG(signum) => ffi::fcntl(fd, ffi::F_SETSIG, signum),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            F_GETSIG => ffi::fcntl(fd, ffi::F_GETSIG),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            F_ADD_SEALS(seals) => ffi::fcntl(fd, ffi::F_ADD_SEALS, seals.bits()),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            F_GET_SEALS => ffi::fcntl(fd, ffi::F_GET_SEALS),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            F_SETPIPE_SZ(size) => ffi::fcntl(fd, ffi::F_SETPIPE_SZ, size),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            F_GETPIPE_SZ => ffi::fcntl(fd, ffi::F_GETPIPE_SZ),
         }
     };
 
@@ -119,9 +573,509 @@ pub fn fcntl(fd: Fd, arg: FcntlArg) -> Result<()> {
         return Err(Error::Sys(Errno::last()));
     }
 
+    Ok(res)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl Owner {
+    fn to_raw(self) -> ffi::f_owner_ex {
+        let (type_, pid) = match self {
+            Owner::Thread(tid) => (ffi::F_OWNER_TID, tid),
+            Owner::Process(pid) => (ffi::F_OWNER_PID, pid),
+            Owner::ProcessGroup(pgrp) => (ffi::F_OWNER_PGRP, pgrp),
+        };
+
+        ffi::f_owner_ex { type_: type_, pid: pid }
+    }
+
+    fn from_raw(raw: &ffi::f_owner_ex) -> Owner {
+        match raw.type_ {
+            ffi::F_OWNER_TID => Owner::Thread(raw.pid),
+            ffi::F_OWNER_PGRP => Owner::ProcessGroup(raw.pid),
+            _ => Owner::Process(raw.pid),
+        }
+    }
+}
+
+/// The operation applied by `flock`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockMode {
+    /// Wait for a shared lock
+    Shared,
+    /// Wait for an exclusive lock
+    Exclusive,
+    /// Take a shared lock or fail with `EWOULDBLOCK`
+    SharedNonblock,
+    /// Take an exclusive lock or fail with `EWOULDBLOCK`
+    ExclusiveNonblock,
+    /// Release the lock
+    Unlock,
+}
+
+/// Apply or remove an advisory lock on the whole of an open file.
+///
+/// `flock` locks belong to the open file description, so they are shared by
+/// duplicated descriptors and across `fork`, and released when the last of
+/// them is closed. They do not interact with `fcntl` record locks.
+///
+/// [Further reading](http://man7.org/linux/man-pages/man2/flock.2.html)
+pub fn flock(fd: Fd, mode: LockMode) -> Result<()> {
+    let operation = match mode {
+        LockMode::Shared => ffi::LOCK_SH,
+        LockMode::Exclusive => ffi::LOCK_EX,
+        LockMode::SharedNonblock => ffi::LOCK_SH | ffi::LOCK_NB,
+        LockMode::ExclusiveNonblock => ffi::LOCK_EX | ffi::LOCK_NB,
+        LockMode::Unlock => ffi::LOCK_UN,
+    };
+
+    let res = unsafe { ffi::flock_fd(fd, operation) };
+    from_ffi(res)
+}
+
+/// The kind of a byte-range lock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockType {
+    Read,
+    Write,
+}
+
+impl LockType {
+    fn to_raw(self) -> ::libc::c_short {
+        match self {
+            LockType::Read => ffi::F_RDLCK,
+            LockType::Write => ffi::F_WRLCK,
+        }
+    }
+}
+
+/// A lock that conflicts with the one queried through `get_lock`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LockConflict {
+    pub lock_type: LockType,
+    pub start: off_t,
+    /// Zero if the lock extends to the end of the file, however large
+    pub len: off_t,
+    /// The process holding the lock. `None` for open file description
+    /// locks, which are not owned by a process.
+    pub pid: Option<pid_t>,
+}
+
+fn lock_range(lock_type: ::libc::c_short, start: off_t, len: off_t) -> flock {
+    let mut lock: flock = unsafe { mem::zeroed() };
+    lock.l_type = lock_type;
+    lock.l_whence = ffi::SEEK_SET;
+    lock.l_start = start;
+    lock.l_len = len;
+    lock
+}
+
+/// Find a lock that would prevent placing a lock of `lock_type` on `len`
+/// bytes starting at `start`, or `None` if the lock could be placed. A `len`
+/// of zero extends the range to the end of the file.
+///
+/// Locks held by the calling process itself never conflict.
+pub fn get_lock(fd: Fd, lock_type: LockType, start: off_t, len: off_t) -> Result<Option<LockConflict>> {
+    let mut lock = lock_range(lock_type.to_raw(), start, len);
+    try!(fcntl(fd, FcntlArg::F_GETLK(&mut lock)));
+
+    let lock_type = match lock.l_type {
+        ffi::F_RDLCK => LockType::Read,
+        ffi::F_WRLCK => LockType::Write,
+        _ => return Ok(None),
+    };
+
+    Ok(Some(LockConflict {
+        lock_type: lock_type,
+        start: lock.l_start,
+        len: lock.l_len,
+        pid: if lock.l_pid > 0 { Some(lock.l_pid) } else { None },
+    }))
+}
+
+/// A POSIX record lock on a byte range, released when the guard is dropped.
+///
+/// POSIX locks belong to the process. They are not inherited by `fork`, and
+/// closing *any* descriptor for the file releases all of the process's locks
+/// on it, so `fd` must stay open for the lifetime of the guard.
+///
+/// For the same reason a process's locks on a file merge rather than stack:
+/// dropping a guard unlocks its whole range, *including any part of it that
+/// another live `PosixLock` in this process also covers*. Avoid overlapping
+/// guards unless that is acceptable; `OfdLock` does not share locks this way.
+/// Dropping ignores unlock errors, which `unlock` reports instead.
+pub struct PosixLock {
+    fd: Fd,
+    start: off_t,
+    len: off_t,
+}
+
+impl PosixLock {
+    /// Place a lock, waiting for conflicting locks to be released
+    pub fn acquire(fd: Fd, lock_type: LockType, start: off_t, len: off_t) -> Result<PosixLock> {
+        let lock = lock_range(lock_type.to_raw(), start, len);
+        try!(fcntl(fd, FcntlArg::F_SETLKW(&lock)));
+        Ok(PosixLock { fd: fd, start: start, len: len })
+    }
+
+    /// Place a lock, or fail with `EAGAIN` or `EACCES` if it conflicts
+    pub fn try_acquire(fd: Fd, lock_type: LockType, start: off_t, len: off_t) -> Result<PosixLock> {
+        let lock = lock_range(lock_type.to_raw(), start, len);
+        try!(fcntl(fd, FcntlArg::F_SETLK(&lock)));
+        Ok(PosixLock { fd: fd, start: start, len: len })
+    }
+
+    /// Release the lock, reporting any error
+    pub fn unlock(self) -> Result<()> {
+        let fd = self.fd;
+        let lock = lock_range(ffi::F_UNLCK, self.start, self.len);
+        mem::forget(self);
+
+        try!(fcntl(fd, FcntlArg::F_SETLK(&lock)));
+        Ok(())
+    }
+}
+
+impl Drop for PosixLock {
+    fn drop(&mut self) {
+        let lock = lock_range(ffi::F_UNLCK, self.start, self.len);
+        let _ = fcntl(self.fd, FcntlArg::F_SETLK(&lock));
+    }
+}
+
+/// An open file description lock on a byte range, released when the guard
+/// is dropped.
+///
+/// Unlike POSIX locks, OFD locks belong to the open file description, so two
+/// `open`s of the same file in one process conflict with each other, and
+/// closing an unrelated descriptor does not release them.
+///
+/// Only available on 64-bit targets.
+#[cfg(all(any(target_os = "linux", target_os = "android"), target_pointer_width = "64"))]
+pub struct OfdLock {
+    fd: Fd,
+    start: off_t,
+    len: off_t,
+}
+
+#[cfg(all(any(target_os = "linux", target_os = "android"), target_pointer_width = "64"))]
+impl OfdLock {
+    /// Place a lock, waiting for conflicting locks to be released
+    pub fn acquire(fd: Fd, lock_type: LockType, start: off_t, len: off_t) -> Result<OfdLock> {
+        let lock = lock_range(lock_type.to_raw(), start, len);
+        try!(fcntl(fd, FcntlArg::F_OFD_SETLKW(&lock)));
+        Ok(OfdLock { fd: fd, start: start, len: len })
+    }
+
+    /// Place a lock, or fail with `EAGAIN` if it conflicts
+    pub fn try_acquire(fd: Fd, lock_type: LockType, start: off_t, len: off_t) -> Result<OfdLock> {
+        let lock = lock_range(lock_type.to_raw(), start, len);
+        try!(fcntl(fd, FcntlArg::F_OFD_SETLK(&lock)));
+        Ok(OfdLock { fd: fd, start: start, len: len })
+    }
+}
+
+#[cfg(all(any(target_os = "linux", target_os = "android"), target_pointer_width = "64"))]
+impl Drop for OfdLock {
+    fn drop(&mut self) {
+        let lock = lock_range(ffi::F_UNLCK, self.start, self.len);
+        let _ = fcntl(self.fd, FcntlArg::F_OFD_SETLK(&lock));
+    }
+}
+
+/// A new file that only gets a name once it is complete, so other processes
+/// never see it partially written.
+///
+/// Where the filesystem supports it the file is created unnamed with
+/// `O_TMPFILE`, otherwise under a hidden temporary name in the same
+/// directory. Write it through `as_raw_fd`, `fsync` it, then `link` or
+/// `replace` it into place. Dropping it before then discards the file.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub struct AtomicFile {
+    fd: Fd,
+    dirfd: DirFd,
+    // The name the file was created under when O_TMPFILE is unsupported,
+    // until it is given its final name
+    temp_name: Option<Vec<u8>>,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl AtomicFile {
+    /// Create a file for reading and writing in the directory `dirfd`, with
+    /// the permissions in `mode` less the umask. `dirfd` must stay open for
+    /// as long as the `AtomicFile`.
+    pub fn new(dirfd: DirFd, mode: Mode) -> Result<AtomicFile> {
+        match openat(dirfd, b".".as_ref(), O_TMPFILE | O_RDWR | O_CLOEXEC, mode) {
+            Ok(fd) => Ok(AtomicFile { fd: fd, dirfd: dirfd, temp_name: None }),
+            // Not supported by the filesystem, or by kernels before 3.11
+            Err(Error::Sys(Errno::EOPNOTSUPP)) |
+            Err(Error::Sys(Errno::EISDIR)) => AtomicFile::new_named(dirfd, mode),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Like `new`, but always creating the file under a hidden temporary
+    /// name rather than unnamed, which `link` then does not need `/proc`
+    /// for.
+    pub fn new_named(dirfd: DirFd, mode: Mode) -> Result<AtomicFile> {
+        loop {
+            let name = temp_name();
+
+            match openat(dirfd, &name[..], O_CREAT | O_EXCL | O_RDWR | O_CLOEXEC, mode) {
+                Ok(fd) => return Ok(AtomicFile { fd: fd, dirfd: dirfd, temp_name: Some(name) }),
+                Err(Error::Sys(Errno::EEXIST)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    pub fn as_raw_fd(&self) -> Fd {
+        self.fd
+    }
+
+    /// Give the file the name `path`, relative to the directory it was
+    /// created in. Fails with `EEXIST` if `path` already exists.
+    pub fn link<P: ?Sized + NixPath>(&mut self, path: &P) -> Result<()> {
+        match self.temp_name.take() {
+            None => {
+                let proc_path = format!("/proc/self/fd/{}", self.fd);
+                linkat(DirFd::Cwd, proc_path.as_bytes(), self.dirfd, path, AT_SYMLINK_FOLLOW)
+            }
+            Some(name) => {
+                if let Err(e) = linkat(self.dirfd, &name[..], self.dirfd, path, AtFlags::empty()) {
+                    self.temp_name = Some(name);
+                    return Err(e);
+                }
+
+                // The file keeps its new name, and Drop retries the removal
+                if let Err(e) = unlinkat(self.dirfd, &name[..], AtFlags::empty()) {
+                    self.temp_name = Some(name);
+                    return Err(e);
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Give the file the name `path`, relative to the directory it was
+    /// created in, atomically replacing any existing file of that name.
+    pub fn replace<P: ?Sized + NixPath>(&mut self, path: &P) -> Result<()> {
+        let name = match self.temp_name.take() {
+            Some(name) => name,
+            // An unnamed file cannot be renamed, so it is first linked under
+            // a temporary name
+            None => try!(self.link_temp()),
+        };
+
+        // Plain renameat, as renameat2 needs a newer kernel than the named
+        // fallback is for
+        if let Err(e) = renameat(self.dirfd, &name[..], self.dirfd, path) {
+            self.temp_name = Some(name);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    fn link_temp(&self) -> Result<Vec<u8>> {
+        let proc_path = format!("/proc/self/fd/{}", self.fd);
+
+        loop {
+            let name = temp_name();
+
+            match linkat(DirFd::Cwd, proc_path.as_bytes(), self.dirfd, &name[..], AT_SYMLINK_FOLLOW) {
+                Ok(()) => return Ok(name),
+                Err(Error::Sys(Errno::EEXIST)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        if let Some(ref name) = self.temp_name {
+            let _ = unlinkat(self.dirfd, &name[..], AtFlags::empty());
+        }
+
+        let _ = close(self.fd);
+    }
+}
+
+// A hidden name that is unique within this process. Other processes may
+// still have taken it, so it is created exclusively and retried.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn temp_name() -> Vec<u8> {
+    use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+
+    static COUNTER: AtomicUsize = ATOMIC_USIZE_INIT;
+
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!(".nix-tmp-{}-{}", unsafe { ::libc::getpid() }, n).into_bytes()
+}
+
+/// Allocate, deallocate or zero the byte range `[offset, offset + len)` of a
+/// file, as selected by `mode`. An empty `mode` allocates the range and
+/// extends the file if needed.
+///
+/// [Further reading](http://man7.org/linux/man-pages/man2/fallocate.2.html)
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn fallocate(fd: Fd, mode: FallocateFlags, offset: off_t, len: off_t) -> Result<()> {
+    let res = unsafe { ffi::fallocate(fd, mode.bits(), offset, len) };
+    from_ffi(res)
+}
+
+/// Ensure that the byte range `[offset, offset + len)` of a file is
+/// allocated, extending the file if needed. Falls back to writing zeros on
+/// filesystems without `fallocate` support.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn posix_fallocate(fd: Fd, offset: off_t, len: off_t) -> Result<()> {
+    // Returns the error instead of setting errno
+    let res = unsafe { ffi::posix_fallocate(fd, offset, len) };
+
+    if res != 0 {
+        return Err(Error::Sys(Errno::from_i32(res)));
+    }
+
     Ok(())
 }
 
+/// The access pattern announced by `posix_fadvise`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(i32)]
+pub enum PosixFadviseAdvice {
+    POSIX_FADV_NORMAL     = 0,
+    POSIX_FADV_RANDOM     = 1,
+    POSIX_FADV_SEQUENTIAL = 2,
+    POSIX_FADV_WILLNEED   = 3,
+    POSIX_FADV_DONTNEED   = 4,
+    POSIX_FADV_NOREUSE    = 5,
+}
+
+/// Announce how the byte range `[offset, offset + len)` of a file will be
+/// accessed. A `len` of zero extends the range to the end of the file.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn posix_fadvise(fd: Fd, offset: off_t, len: off_t, advice: PosixFadviseAdvice) -> Result<()> {
+    // Returns the error instead of setting errno
+    let res = unsafe { ffi::posix_fadvise(fd, offset, len, advice as c_int) };
+
+    if res != 0 {
+        return Err(Error::Sys(Errno::from_i32(res)));
+    }
+
+    Ok(())
+}
+
+/// Start reading `count` bytes at `offset` into the page cache, without
+/// waiting for the read to complete.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn readahead(fd: Fd, offset: i64, count: usize) -> Result<()> {
+    use libc::size_t;
+
+    let res = unsafe { ffi::readahead(fd, offset, count as size_t) };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(())
+}
+
+/// Write out the dirty pages in the byte range `[offset, offset + nbytes)` of
+/// a file. A `nbytes` of zero extends the range to the end of the file.
+///
+/// This flushes neither the file's metadata nor the disk's write cache, so
+/// it does not guarantee durability on its own; see `fdatasync`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn sync_file_range(fd: Fd, offset: i64, nbytes: i64, flags: SyncFileRangeFlags) -> Result<()> {
+    let res = unsafe { ffi::sync_file_range(fd, offset, nbytes, flags.bits()) };
+    from_ffi(res)
+}
+
+// None becomes a null offset, meaning the descriptor's file offset
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn offset_ptr(off: Option<&mut i64>) -> *mut i64 {
+    off.map_or(::std::ptr::null_mut(), |off| off as *mut i64)
+}
+
+/// Move up to `len` bytes from `fd_in` to `fd_out` without copying them
+/// through userspace. At least one of the descriptors must be a pipe.
+///
+/// For a descriptor that is not a pipe, `Some(offset)` reads or writes at
+/// that offset and advances it instead of the file offset; for a pipe it
+/// must be `None`. Returns the number of bytes moved, zero at end of input.
+///
+/// [Further reading](http://man7.org/linux/man-pages/man2/splice.2.html)
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn splice(fd_in: Fd, off_in: Option<&mut i64>, fd_out: Fd, off_out: Option<&mut i64>,
+              len: usize, flags: SpliceFFlags) -> Result<usize> {
+    use libc::size_t;
+
+    let res = unsafe {
+        ffi::splice(fd_in, offset_ptr(off_in), fd_out, offset_ptr(off_out), len as size_t, flags.bits())
+    };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res as usize)
+}
+
+/// Copy up to `len` bytes from the pipe `fd_in` to the pipe `fd_out`
+/// without consuming them, so they can still be read from `fd_in`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn tee(fd_in: Fd, fd_out: Fd, len: usize, flags: SpliceFFlags) -> Result<usize> {
+    use libc::size_t;
+
+    let res = unsafe { ffi::tee(fd_in, fd_out, len as size_t, flags.bits()) };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res as usize)
+}
+
+/// Splice the buffers in `iov` into the pipe `fd`. With `SPLICE_F_GIFT` the
+/// pages may be moved rather than copied, so the caller must not modify
+/// them afterwards.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn vmsplice(fd: Fd, iov: &[IoVec<&[u8]>], flags: SpliceFFlags) -> Result<usize> {
+    use libc::size_t;
+
+    let res = unsafe { ffi::vmsplice(fd, iov.as_ptr(), iov.len() as size_t, flags.bits()) };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res as usize)
+}
+
+/// Copy up to `len` bytes between two regular files inside the kernel,
+/// sharing extents where the filesystem supports it. The offsets behave as
+/// for `splice`. Returns the number of bytes copied, zero at end of input.
+///
+/// [Further reading](http://man7.org/linux/man-pages/man2/copy_file_range.2.html)
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn copy_file_range(fd_in: Fd, off_in: Option<&mut i64>, fd_out: Fd, off_out: Option<&mut i64>,
+                       len: usize) -> Result<usize> {
+    use libc::size_t;
+
+    let res = unsafe {
+        ffi::copy_file_range(fd_in, offset_ptr(off_in), fd_out, offset_ptr(off_out), len as size_t, 0)
+    };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res as usize)
+}
+
 #[cfg(any(target_os = "linux", target_os = "android"))]
 mod consts {
     use libc::c_int;
@@ -139,6 +1093,7 @@ mod consts {
             const O_APPEND    = 0o00002000,
             const O_NONBLOCK  = 0o00004000,
             const O_DSYNC     = 0o00010000,
+            const O_ASYNC     = 0o00020000,
             const O_DIRECT    = 0o00040000,
             const O_LARGEFILE = 0o00100000,
             const O_DIRECTORY = 0o00200000,
@@ -147,7 +1102,7 @@ mod consts {
             const O_CLOEXEC   = 0o02000000,
             const O_SYNC      = 0o04000000,
             const O_PATH      = 0o10000000,
-            const O_TMPFILE   = 0o20000000,
+            const O_TMPFILE   = 0o20000000 | O_DIRECTORY.bits,
             const O_NDELAY    = O_NONBLOCK.bits
         }
     );
@@ -157,6 +1112,86 @@ mod consts {
             const FD_CLOEXEC = 1
         }
     );
+
+    pub const AT_FDCWD: c_int = -100;
+
+    bitflags!(
+        flags AtFlags: c_int {
+            const AT_SYMLINK_NOFOLLOW = 0x100,
+            const AT_REMOVEDIR        = 0x200,
+            const AT_EACCESS          = 0x200,
+            const AT_SYMLINK_FOLLOW   = 0x400,
+            const AT_NO_AUTOMOUNT     = 0x800,
+            const AT_EMPTY_PATH       = 0x1000,
+            const AT_STATX_FORCE_SYNC = 0x2000,
+            const AT_STATX_DONT_SYNC  = 0x4000
+        }
+    );
+
+    bitflags!(
+        flags RenameFlags: c_int {
+            const RENAME_NOREPLACE = 1 << 0, // Since Linux 3.15
+            const RENAME_EXCHANGE  = 1 << 1, // Since Linux 3.15
+            const RENAME_WHITEOUT  = 1 << 2  // Since Linux 3.18
+        }
+    );
+
+    bitflags!(
+        flags ResolveFlag: u64 {
+            const RESOLVE_NO_XDEV       = 0x01,
+            const RESOLVE_NO_MAGICLINKS = 0x02,
+            const RESOLVE_NO_SYMLINKS   = 0x04,
+            const RESOLVE_BENEATH       = 0x08,
+            const RESOLVE_IN_ROOT       = 0x10,
+            const RESOLVE_CACHED        = 0x20  // Since Linux 5.12
+        }
+    );
+
+    bitflags!(
+        flags FallocateFlags: c_int {
+            const FALLOC_FL_KEEP_SIZE      = 0x01,
+            const FALLOC_FL_PUNCH_HOLE     = 0x02, // Since Linux 2.6.38
+            const FALLOC_FL_NO_HIDE_STALE  = 0x04,
+            const FALLOC_FL_COLLAPSE_RANGE = 0x08, // Since Linux 3.15
+            const FALLOC_FL_ZERO_RANGE     = 0x10, // Since Linux 3.15
+            const FALLOC_FL_INSERT_RANGE   = 0x20, // Since Linux 4.1
+            const FALLOC_FL_UNSHARE_RANGE  = 0x40  // Since Linux 4.21
+        }
+    );
+
+    bitflags!(
+        flags SealFlag: c_int {
+            /// Prevent adding further seals
+            const F_SEAL_SEAL         = 0x0001,
+            /// Prevent the file from shrinking
+            const F_SEAL_SHRINK       = 0x0002,
+            /// Prevent the file from growing
+            const F_SEAL_GROW         = 0x0004,
+            /// Prevent writes. Fails with `EBUSY` while the file has
+            /// writable shared mappings.
+            const F_SEAL_WRITE        = 0x0008,
+            /// Prevent new writes, while existing writable mappings keep
+            /// working (since Linux 5.1)
+            const F_SEAL_FUTURE_WRITE = 0x0010
+        }
+    );
+
+    bitflags!(
+        flags SpliceFFlags: ::libc::c_uint {
+            const SPLICE_F_MOVE     = 1,
+            const SPLICE_F_NONBLOCK = 2,
+            const SPLICE_F_MORE     = 4,
+            const SPLICE_F_GIFT     = 8
+        }
+    );
+
+    bitflags!(
+        flags SyncFileRangeFlags: ::libc::c_uint {
+            const SYNC_FILE_RANGE_WAIT_BEFORE = 1,
+            const SYNC_FILE_RANGE_WRITE       = 2,
+            const SYNC_FILE_RANGE_WAIT_AFTER  = 4
+        }
+    );
 }
 
 #[cfg(any(target_os = "macos", target_os = "ios"))]
@@ -175,6 +1210,7 @@ mod consts {
             const O_TRUNC     = 0x0000400,
             const O_APPEND    = 0x0000008,
             const O_NONBLOCK  = 0x0000004,
+            const O_ASYNC     = 0x0000040,
             const O_DSYNC     = 0x0400000,
             const O_DIRECTORY = 0x0100000,
             const O_NOFOLLOW  = 0x0000100,
@@ -190,4 +1226,14 @@ mod consts {
             const FD_CLOEXEC = 1
         }
     );
+    pub const AT_FDCWD: c_int = -2;
+
+    bitflags!(
+        flags AtFlags: c_int {
+            const AT_EACCESS          = 0x0010,
+            const AT_SYMLINK_NOFOLLOW = 0x0020,
+            const AT_SYMLINK_FOLLOW   = 0x0040,
+            const AT_REMOVEDIR        = 0x0080
+        }
+    );
 }
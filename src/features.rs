@@ -7,6 +7,7 @@ mod os {
     // Features:
     // * atomic cloexec on socket: 2.6.27
     // * pipe2: 2.6.27
+    // * dup3: 2.6.27
     // * accept4: 2.6.28
 
     static VERS_UNKNOWN: usize = 1;
@@ -86,6 +87,18 @@ mod os {
         kernel_version() >= VERS_2_6_27
     }
 
+    pub fn pipe2_available() -> bool {
+        kernel_version() >= VERS_2_6_27
+    }
+
+    pub fn dup3_available() -> bool {
+        kernel_version() >= VERS_2_6_27
+    }
+
+    pub fn accept4_available() -> bool {
+        kernel_version() >= VERS_2_6_28
+    }
+
     #[test]
     pub fn test_parsing_kernel_version() {
         assert!(kernel_version() > 0);
@@ -97,4 +110,16 @@ mod os {
     pub fn socket_atomic_cloexec() -> bool {
         false
     }
+
+    pub fn pipe2_available() -> bool {
+        false
+    }
+
+    pub fn dup3_available() -> bool {
+        false
+    }
+
+    pub fn accept4_available() -> bool {
+        false
+    }
 }
@@ -17,6 +17,9 @@ extern crate nix_test as nixtest;
 // Re-export some libc constants
 pub use libc::{c_int, c_void};
 
+#[cfg(unix)]
+pub mod dir;
+
 #[cfg(unix)]
 pub mod errno;
 
@@ -0,0 +1,454 @@
+//! Monitoring filesystem events across mounts and filesystems
+//!
+//! Unlike inotify, fanotify can watch whole mounts or filesystems, reports
+//! the process responsible for an event, and can ask the listener for
+//! permission before an access is allowed. Events are read from the fanotify
+//! descriptor as `fanotify_event_metadata` records, optionally followed by
+//! information records such as file handles; `Events` decodes both.
+//!
+//! Most operations require `CAP_SYS_ADMIN`.
+//!
+//! [Further reading](http://man7.org/linux/man-pages/man7/fanotify.7.html)
+use {Error, Result, NixPath, AsExtStr};
+use errno::Errno;
+use fcntl::{Fd, DirFd, FileHandle, OFlag};
+use libc::{c_int, c_uint};
+use std::{mem, ptr};
+use unistd::{close, read, write};
+
+mod ffi {
+    use libc::{c_char, c_int, c_uint};
+
+    pub const FANOTIFY_METADATA_VERSION: u8 = 3;
+    pub const FAN_NOFD: c_int = -1;
+
+    #[repr(C)]
+    pub struct fanotify_event_metadata {
+        pub event_len: u32,
+        pub vers: u8,
+        pub reserved: u8,
+        pub metadata_len: u16,
+        pub mask: u64,
+        pub fd: i32,
+        pub pid: i32,
+    }
+
+    #[repr(C)]
+    pub struct fanotify_event_info_header {
+        pub info_type: u8,
+        pub pad: u8,
+        pub len: u16,
+    }
+
+    // Followed by a struct file_handle, and for the *_DFID_NAME records by
+    // a NUL-terminated name
+    #[repr(C)]
+    pub struct fanotify_event_info_fid {
+        pub hdr: fanotify_event_info_header,
+        pub fsid: [i32; 2],
+    }
+
+    #[repr(C)]
+    pub struct file_handle {
+        pub handle_bytes: u32,
+        pub handle_type: i32,
+    }
+
+    #[repr(C)]
+    pub struct fanotify_event_info_pidfd {
+        pub hdr: fanotify_event_info_header,
+        pub pidfd: i32,
+    }
+
+    #[repr(C)]
+    pub struct fanotify_response {
+        pub fd: i32,
+        pub response: u32,
+    }
+
+    extern {
+        pub fn fanotify_init(flags: c_uint, event_f_flags: c_uint) -> c_int;
+        pub fn fanotify_mark(fanotify_fd: c_int, flags: c_uint, mask: u64,
+                             dirfd: c_int, pathname: *const c_char) -> c_int;
+    }
+}
+
+bitflags!(
+    flags InitFlags: c_uint {
+        const FAN_CLOEXEC           = 0x00000001,
+        const FAN_NONBLOCK          = 0x00000002,
+        const FAN_CLASS_NOTIF       = 0x00000000,
+        const FAN_CLASS_CONTENT     = 0x00000004,
+        const FAN_CLASS_PRE_CONTENT = 0x00000008,
+        const FAN_UNLIMITED_QUEUE   = 0x00000010,
+        const FAN_UNLIMITED_MARKS   = 0x00000020,
+        const FAN_ENABLE_AUDIT      = 0x00000040, // Since Linux 4.15
+        const FAN_REPORT_PIDFD      = 0x00000080, // Since Linux 5.15
+        const FAN_REPORT_TID        = 0x00000100, // Since Linux 4.20
+        const FAN_REPORT_FID        = 0x00000200, // Since Linux 5.1
+        const FAN_REPORT_DIR_FID    = 0x00000400, // Since Linux 5.9
+        const FAN_REPORT_NAME       = 0x00000800, // Since Linux 5.9
+        const FAN_REPORT_TARGET_FID = 0x00001000, // Since Linux 5.17
+        const FAN_REPORT_DFID_NAME  = 0x00000c00
+    }
+);
+
+bitflags!(
+    flags MarkFlags: c_uint {
+        const FAN_MARK_ADD                 = 0x00000001,
+        const FAN_MARK_REMOVE              = 0x00000002,
+        const FAN_MARK_DONT_FOLLOW         = 0x00000004,
+        const FAN_MARK_ONLYDIR             = 0x00000008,
+        const FAN_MARK_INODE               = 0x00000000,
+        const FAN_MARK_MOUNT               = 0x00000010,
+        const FAN_MARK_IGNORED_MASK        = 0x00000020,
+        const FAN_MARK_IGNORED_SURV_MODIFY = 0x00000040,
+        const FAN_MARK_FLUSH               = 0x00000080,
+        const FAN_MARK_FILESYSTEM          = 0x00000100, // Since Linux 4.20
+        const FAN_MARK_EVICTABLE           = 0x00000200, // Since Linux 5.19
+        const FAN_MARK_IGNORE              = 0x00000400  // Since Linux 6.0
+    }
+);
+
+bitflags!(
+    flags MaskFlags: u64 {
+        const FAN_ACCESS         = 0x00000001,
+        const FAN_MODIFY         = 0x00000002,
+        const FAN_ATTRIB         = 0x00000004, // Since Linux 5.1
+        const FAN_CLOSE_WRITE    = 0x00000008,
+        const FAN_CLOSE_NOWRITE  = 0x00000010,
+        const FAN_CLOSE          = 0x00000018,
+        const FAN_OPEN           = 0x00000020,
+        const FAN_MOVED_FROM     = 0x00000040, // Since Linux 5.1
+        const FAN_MOVED_TO       = 0x00000080, // Since Linux 5.1
+        const FAN_MOVE           = 0x000000c0,
+        const FAN_CREATE         = 0x00000100, // Since Linux 5.1
+        const FAN_DELETE         = 0x00000200, // Since Linux 5.1
+        const FAN_DELETE_SELF    = 0x00000400, // Since Linux 5.1
+        const FAN_MOVE_SELF      = 0x00000800, // Since Linux 5.1
+        const FAN_OPEN_EXEC      = 0x00001000, // Since Linux 5.0
+        const FAN_Q_OVERFLOW     = 0x00004000,
+        const FAN_FS_ERROR       = 0x00008000, // Since Linux 5.16
+        const FAN_OPEN_PERM      = 0x00010000,
+        const FAN_ACCESS_PERM    = 0x00020000,
+        const FAN_OPEN_EXEC_PERM = 0x00040000, // Since Linux 5.0
+        const FAN_EVENT_ON_CHILD = 0x08000000,
+        const FAN_RENAME         = 0x10000000, // Since Linux 5.17
+        const FAN_ONDIR          = 0x40000000
+    }
+);
+
+bitflags!(
+    flags Response: u32 {
+        const FAN_ALLOW = 0x01,
+        const FAN_DENY  = 0x02,
+        const FAN_AUDIT = 0x10
+    }
+);
+
+// Types of the information records following an event
+pub const FAN_EVENT_INFO_TYPE_FID: u8           = 1;
+pub const FAN_EVENT_INFO_TYPE_DFID_NAME: u8     = 2;
+pub const FAN_EVENT_INFO_TYPE_DFID: u8          = 3;
+pub const FAN_EVENT_INFO_TYPE_PIDFD: u8         = 4;
+pub const FAN_EVENT_INFO_TYPE_ERROR: u8         = 5;
+pub const FAN_EVENT_INFO_TYPE_OLD_DFID_NAME: u8 = 10;
+pub const FAN_EVENT_INFO_TYPE_NEW_DFID_NAME: u8 = 12;
+
+/// A fanotify group. The descriptor is closed when it is dropped.
+pub struct Fanotify {
+    fd: Fd,
+}
+
+impl Fanotify {
+    /// Create a new fanotify group. `event_f_flags` are the status flags,
+    /// such as `O_RDONLY | O_CLOEXEC`, of the descriptors opened for events.
+    pub fn init(flags: InitFlags, event_f_flags: OFlag) -> Result<Fanotify> {
+        let res = unsafe { ffi::fanotify_init(flags.bits(), event_f_flags.bits() as c_uint) };
+
+        if res < 0 {
+            return Err(Error::Sys(Errno::last()));
+        }
+
+        Ok(Fanotify { fd: res })
+    }
+
+    /// Add, remove or flush the marks on the file, mount or filesystem
+    /// containing `path`, resolved relative to `dirfd`.
+    pub fn mark<P: ?Sized + NixPath>(&self, flags: MarkFlags, mask: MaskFlags, dirfd: DirFd, path: &P) -> Result<()> {
+        let res = try!(path.with_nix_path(|osstr| unsafe {
+            ffi::fanotify_mark(self.fd, flags.bits(), mask.bits(), dirfd.as_raw(), osstr.as_ext_str())
+        }));
+
+        if res < 0 {
+            return Err(Error::Sys(Errno::last()));
+        }
+
+        Ok(())
+    }
+
+    /// Like `mark`, but for the object referred to by the open descriptor
+    /// `fd`.
+    pub fn mark_fd(&self, flags: MarkFlags, mask: MaskFlags, fd: Fd) -> Result<()> {
+        let res = unsafe {
+            ffi::fanotify_mark(self.fd, flags.bits(), mask.bits(), fd, ptr::null())
+        };
+
+        if res < 0 {
+            return Err(Error::Sys(Errno::last()));
+        }
+
+        Ok(())
+    }
+
+    /// Read the queued events into `buf` and return an iterator decoding
+    /// them.
+    pub fn read_events<'a>(&self, buf: &'a mut [u8]) -> Result<Events<'a>> {
+        let len = try!(read(self.fd, buf));
+        Ok(Events::new(&buf[..len]))
+    }
+
+    /// Answer a permission event. `fd` is the descriptor reported with the
+    /// event; it still has to be closed afterwards.
+    pub fn write_response(&self, fd: Fd, response: Response) -> Result<()> {
+        let resp = ffi::fanotify_response {
+            fd: fd,
+            response: response.bits(),
+        };
+
+        let bytes = unsafe {
+            ::std::slice::from_raw_parts(&resp as *const ffi::fanotify_response as *const u8,
+                                         mem::size_of::<ffi::fanotify_response>())
+        };
+
+        try!(write(self.fd, bytes));
+        Ok(())
+    }
+
+    pub fn as_raw_fd(&self) -> Fd {
+        self.fd
+    }
+}
+
+impl Drop for Fanotify {
+    fn drop(&mut self) {
+        let _ = close(self.fd);
+    }
+}
+
+/// A decoded `fanotify_event_metadata` record.
+#[derive(Clone, Copy)]
+pub struct FanotifyEvent<'a> {
+    pub mask: MaskFlags,
+    /// A descriptor open on the object the event is about, which the caller
+    /// must close. `None` for queue overflows and when the group reports
+    /// file handles instead.
+    pub fd: Option<Fd>,
+    /// The process, or with `FAN_REPORT_TID` the thread, that caused the
+    /// event
+    pub pid: i32,
+    info: &'a [u8],
+}
+
+impl<'a> FanotifyEvent<'a> {
+    /// Returns the information records following the event
+    pub fn info(&self) -> InfoRecords<'a> {
+        InfoRecords { buf: self.info }
+    }
+}
+
+/// A file handle reported by a group initialized with one of the
+/// `FAN_REPORT_*FID` flags.
+#[derive(Clone, Copy)]
+pub struct FidInfo<'a> {
+    /// One of the `FAN_EVENT_INFO_TYPE_*FID*` constants
+    pub info_type: u8,
+    pub fsid: [i32; 2],
+    pub handle_type: i32,
+    pub handle: &'a [u8],
+    /// For the `*_DFID_NAME` types, the name of the entry within the
+    /// directory identified by the handle
+    pub name: Option<&'a [u8]>,
+}
+
+impl<'a> FidInfo<'a> {
+    /// The handle, for use with `fcntl::open_by_handle_at`
+    pub fn file_handle(&self) -> FileHandle {
+        FileHandle::new(self.handle_type, self.handle.to_vec())
+    }
+}
+
+/// An information record following an event.
+#[derive(Clone, Copy)]
+pub enum InfoRecord<'a> {
+    Fid(FidInfo<'a>),
+    /// A pidfd for the process that caused the event, which the caller must
+    /// close
+    Pidfd(Fd),
+    /// A record this module does not decode, with its type and the bytes
+    /// following its header
+    Other(u8, &'a [u8]),
+}
+
+/// An iterator over the `fanotify_event_metadata` records in a buffer filled
+/// by reading a fanotify descriptor.
+pub struct Events<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Events<'a> {
+    pub fn new(buf: &'a [u8]) -> Events<'a> {
+        Events { buf: buf }
+    }
+}
+
+impl<'a> Iterator for Events<'a> {
+    type Item = FanotifyEvent<'a>;
+
+    fn next(&mut self) -> Option<FanotifyEvent<'a>> {
+        let meta: ffi::fanotify_event_metadata = match read_struct(self.buf) {
+            Some(meta) => meta,
+            None => return None,
+        };
+
+        let event_len = meta.event_len as usize;
+        let metadata_len = meta.metadata_len as usize;
+
+        let header_len = mem::size_of::<ffi::fanotify_event_metadata>();
+
+        if meta.vers != ffi::FANOTIFY_METADATA_VERSION ||
+            event_len < header_len || metadata_len < header_len ||
+            event_len > self.buf.len() || metadata_len > event_len {
+            return None;
+        }
+
+        let info = &self.buf[metadata_len..event_len];
+        self.buf = &self.buf[event_len..];
+
+        Some(FanotifyEvent {
+            mask: MaskFlags::from_bits_truncate(meta.mask),
+            fd: if meta.fd == ffi::FAN_NOFD { None } else { Some(meta.fd) },
+            pid: meta.pid,
+            info: info,
+        })
+    }
+}
+
+/// An iterator over the information records of an event.
+pub struct InfoRecords<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Iterator for InfoRecords<'a> {
+    type Item = InfoRecord<'a>;
+
+    fn next(&mut self) -> Option<InfoRecord<'a>> {
+        let hdr: ffi::fanotify_event_info_header = match read_struct(self.buf) {
+            Some(hdr) => hdr,
+            None => return None,
+        };
+
+        let len = hdr.len as usize;
+
+        if len < mem::size_of::<ffi::fanotify_event_info_header>() || len > self.buf.len() {
+            return None;
+        }
+
+        let record = &self.buf[..len];
+        self.buf = &self.buf[len..];
+
+        match hdr.info_type {
+            FAN_EVENT_INFO_TYPE_FID |
+            FAN_EVENT_INFO_TYPE_DFID_NAME |
+            FAN_EVENT_INFO_TYPE_DFID |
+            FAN_EVENT_INFO_TYPE_OLD_DFID_NAME |
+            FAN_EVENT_INFO_TYPE_NEW_DFID_NAME => decode_fid(hdr.info_type, record),
+            FAN_EVENT_INFO_TYPE_PIDFD => {
+                read_struct::<ffi::fanotify_event_info_pidfd>(record)
+                    .map(|info| InfoRecord::Pidfd(info.pidfd as c_int))
+            }
+            info_type => {
+                let header_len = mem::size_of::<ffi::fanotify_event_info_header>();
+                Some(InfoRecord::Other(info_type, &record[header_len..]))
+            }
+        }
+    }
+}
+
+fn decode_fid<'a>(info_type: u8, record: &'a [u8]) -> Option<InfoRecord<'a>> {
+    let fid: ffi::fanotify_event_info_fid = match read_struct(record) {
+        Some(fid) => fid,
+        None => return None,
+    };
+
+    let rest = &record[mem::size_of::<ffi::fanotify_event_info_fid>()..];
+    let fh: ffi::file_handle = match read_struct(rest) {
+        Some(fh) => fh,
+        None => return None,
+    };
+
+    let rest = &rest[mem::size_of::<ffi::file_handle>()..];
+    let handle_len = fh.handle_bytes as usize;
+
+    if handle_len > rest.len() {
+        return None;
+    }
+
+    let name = match info_type {
+        FAN_EVENT_INFO_TYPE_DFID_NAME |
+        FAN_EVENT_INFO_TYPE_OLD_DFID_NAME |
+        FAN_EVENT_INFO_TYPE_NEW_DFID_NAME => {
+            // NUL-terminated, then padded to the record's alignment
+            let name = &rest[handle_len..];
+            Some(&name[..name.iter().position(|b| *b == 0).unwrap_or(name.len())])
+        }
+        _ => None,
+    };
+
+    Some(InfoRecord::Fid(FidInfo {
+        info_type: info_type,
+        fsid: fid.fsid,
+        handle_type: fh.handle_type,
+        handle: &rest[..handle_len],
+        name: name,
+    }))
+}
+
+// Records are not necessarily aligned within the buffer, so they are copied
+// out rather than referenced in place.
+fn read_struct<T>(buf: &[u8]) -> Option<T> {
+    let len = mem::size_of::<T>();
+
+    if buf.len() < len {
+        return None;
+    }
+
+    unsafe {
+        let mut val: T = mem::uninitialized();
+        ptr::copy_nonoverlapping(buf.as_ptr(), &mut val as *mut T as *mut u8, len);
+        Some(val)
+    }
+}
+
+#[test]
+fn test_fanotify_sizes() {
+    assert_eq!(mem::size_of::<ffi::fanotify_event_metadata>(), 24);
+    assert_eq!(mem::size_of::<ffi::fanotify_event_info_fid>(), 12);
+    assert_eq!(mem::size_of::<ffi::fanotify_response>(), 8);
+}
+
+#[test]
+fn test_fanotify_events_short_lengths() {
+    fn event(event_len: u32, metadata_len: u16) -> [u8; 24] {
+        let mut meta: ffi::fanotify_event_metadata = unsafe { mem::zeroed() };
+        meta.event_len = event_len;
+        meta.vers = ffi::FANOTIFY_METADATA_VERSION;
+        meta.metadata_len = metadata_len;
+
+        unsafe { mem::transmute(meta) }
+    }
+
+    assert!(Events::new(&event(0, 24)).next().is_none());
+    assert!(Events::new(&event(24, 8)).next().is_none());
+    assert!(Events::new(&event(24, 24)).next().is_some());
+}
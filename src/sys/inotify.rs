@@ -0,0 +1,199 @@
+//! Monitoring filesystem events
+//!
+//! An `Inotify` instance is a file descriptor that becomes readable when one
+//! of its watches fires, so it can be registered with `sys::epoll` like any
+//! other descriptor. Reading it yields a sequence of variable-length
+//! `inotify_event` records, which `Events` decodes.
+//!
+//! [Further reading](http://man7.org/linux/man-pages/man7/inotify.7.html)
+use {Error, Result, NixPath, AsExtStr, from_ffi};
+use errno::Errno;
+use fcntl::Fd;
+use libc::c_int;
+use std::{mem, ptr};
+use unistd::{close, read};
+
+mod ffi {
+    use libc::{c_char, c_int};
+
+    #[repr(C)]
+    pub struct inotify_event {
+        pub wd: c_int,
+        pub mask: u32,
+        pub cookie: u32,
+        pub len: u32,
+    }
+
+    extern {
+        pub fn inotify_init1(flags: c_int) -> c_int;
+        pub fn inotify_add_watch(fd: c_int, pathname: *const c_char, mask: u32) -> c_int;
+        pub fn inotify_rm_watch(fd: c_int, wd: c_int) -> c_int;
+    }
+}
+
+bitflags!(
+    flags InitFlags: c_int {
+        const IN_CLOEXEC  = 0o2000000,
+        const IN_NONBLOCK = 0o0004000
+    }
+);
+
+bitflags!(
+    flags AddWatchFlags: u32 {
+        const IN_ACCESS        = 0x00000001,
+        const IN_MODIFY        = 0x00000002,
+        const IN_ATTRIB        = 0x00000004,
+        const IN_CLOSE_WRITE   = 0x00000008,
+        const IN_CLOSE_NOWRITE = 0x00000010,
+        const IN_CLOSE         = 0x00000018,
+        const IN_OPEN          = 0x00000020,
+        const IN_MOVED_FROM    = 0x00000040,
+        const IN_MOVED_TO      = 0x00000080,
+        const IN_MOVE          = 0x000000c0,
+        const IN_CREATE        = 0x00000100,
+        const IN_DELETE        = 0x00000200,
+        const IN_DELETE_SELF   = 0x00000400,
+        const IN_MOVE_SELF     = 0x00000800,
+        const IN_ALL_EVENTS    = 0x00000fff,
+
+        // Only ever reported in events
+        const IN_UNMOUNT       = 0x00002000,
+        const IN_Q_OVERFLOW    = 0x00004000,
+        const IN_IGNORED       = 0x00008000,
+        const IN_ISDIR         = 0x40000000,
+
+        // Only accepted by add_watch
+        const IN_ONLYDIR       = 0x01000000,
+        const IN_DONT_FOLLOW   = 0x02000000,
+        const IN_EXCL_UNLINK   = 0x04000000, // Since Linux 2.6.36
+        const IN_MASK_CREATE   = 0x10000000, // Since Linux 4.18
+        const IN_MASK_ADD      = 0x20000000,
+        const IN_ONESHOT       = 0x80000000
+    }
+);
+
+/// Identifies a watch within its `Inotify` instance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WatchDescriptor(c_int);
+
+/// An inotify instance. The descriptor is closed when it is dropped.
+pub struct Inotify {
+    fd: Fd,
+}
+
+impl Inotify {
+    /// Create a new instance. `IN_NONBLOCK` makes `read_events` fail with
+    /// `EAGAIN` instead of blocking when no events are queued.
+    pub fn init(flags: InitFlags) -> Result<Inotify> {
+        let res = unsafe { ffi::inotify_init1(flags.bits()) };
+
+        if res < 0 {
+            return Err(Error::Sys(Errno::last()));
+        }
+
+        Ok(Inotify { fd: res })
+    }
+
+    /// Watch `path` for the events in `mask`. Adding a watch for a file that
+    /// is already watched replaces its mask, unless `IN_MASK_ADD` is given,
+    /// and returns the existing descriptor.
+    pub fn add_watch<P: ?Sized + NixPath>(&self, path: &P, mask: AddWatchFlags) -> Result<WatchDescriptor> {
+        let res = try!(path.with_nix_path(|osstr| {
+            unsafe { ffi::inotify_add_watch(self.fd, osstr.as_ext_str(), mask.bits()) }
+        }));
+
+        if res < 0 {
+            return Err(Error::Sys(Errno::last()));
+        }
+
+        Ok(WatchDescriptor(res))
+    }
+
+    /// Remove a watch. An `IN_IGNORED` event is queued for it.
+    pub fn rm_watch(&self, wd: WatchDescriptor) -> Result<()> {
+        let res = unsafe { ffi::inotify_rm_watch(self.fd, wd.0) };
+        from_ffi(res)
+    }
+
+    /// Read the queued events into `buf` and return an iterator decoding
+    /// them. `buf` must be able to hold at least one event with its name,
+    /// `size_of::<inotify_event>() + NAME_MAX + 1` bytes, or the read fails
+    /// with `EINVAL`.
+    pub fn read_events<'a>(&self, buf: &'a mut [u8]) -> Result<Events<'a>> {
+        let len = try!(read(self.fd, buf));
+        Ok(Events::new(&buf[..len]))
+    }
+
+    pub fn as_raw_fd(&self) -> Fd {
+        self.fd
+    }
+}
+
+impl Drop for Inotify {
+    fn drop(&mut self) {
+        let _ = close(self.fd);
+    }
+}
+
+/// A decoded `inotify_event` record.
+#[derive(Clone, Copy)]
+pub struct InotifyEvent<'a> {
+    pub wd: WatchDescriptor,
+    pub mask: AddWatchFlags,
+    /// Connects the `IN_MOVED_FROM` and `IN_MOVED_TO` events of a rename
+    pub cookie: u32,
+    /// For watched directories, the name of the entry the event is about
+    pub name: Option<&'a [u8]>,
+}
+
+/// An iterator over the `inotify_event` records in a buffer filled by
+/// reading an inotify descriptor.
+pub struct Events<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Events<'a> {
+    pub fn new(buf: &'a [u8]) -> Events<'a> {
+        Events { buf: buf }
+    }
+}
+
+impl<'a> Iterator for Events<'a> {
+    type Item = InotifyEvent<'a>;
+
+    fn next(&mut self) -> Option<InotifyEvent<'a>> {
+        let header_len = mem::size_of::<ffi::inotify_event>();
+
+        if self.buf.len() < header_len {
+            return None;
+        }
+
+        // The buffer is not necessarily aligned for the header
+        let header: ffi::inotify_event = unsafe {
+            let mut header = mem::uninitialized();
+            ptr::copy_nonoverlapping(self.buf.as_ptr(),
+                                     &mut header as *mut ffi::inotify_event as *mut u8,
+                                     header_len);
+            header
+        };
+
+        let end = header_len + header.len as usize;
+
+        if self.buf.len() < end {
+            return None;
+        }
+
+        // The name is padded with NULs to an aligned length
+        let name = &self.buf[header_len..end];
+        let name = &name[..name.iter().position(|b| *b == 0).unwrap_or(name.len())];
+
+        self.buf = &self.buf[end..];
+
+        Some(InotifyEvent {
+            wd: WatchDescriptor(header.wd),
+            mask: AddWatchFlags::from_bits_truncate(header.mask),
+            cookie: header.cookie,
+            name: if name.is_empty() { None } else { Some(name) },
+        })
+    }
+}
@@ -20,6 +20,7 @@ mod ffi {
     pub mod os {
         use libc::c_ulong;
         pub const TIOCGWINSZ: c_ulong = 0x40087468;
+        pub const FIONREAD: c_ulong = 0x4004667f;
     }
 
     #[cfg(any(target_os = "linux",
@@ -27,11 +28,15 @@ mod ffi {
     pub mod os {
         use libc::c_int;
         pub const TIOCGWINSZ: c_int = 0x5413;
+        pub const FIONREAD: c_int = 0x541b;
     }
 }
 
 pub enum IoctlArg<'a> {
-    TIOCGWINSZ(&'a mut Winsize)
+    TIOCGWINSZ(&'a mut Winsize),
+    /// The number of bytes that can be read without blocking, e.g. from a
+    /// pipe, socket or terminal
+    FIONREAD(&'a mut libc::c_int),
 }
 
 pub fn ioctl(fd: Fd, arg: IoctlArg) -> Result<()> {
@@ -41,5 +46,10 @@ pub fn ioctl(fd: Fd, arg: IoctlArg) -> Result<()> {
                 libc::funcs::bsd44::ioctl(fd, ffi::os::TIOCGWINSZ, winsize)
             })
         }
+        FIONREAD(&mut ref mut count) => {
+            from_ffi(unsafe {
+                libc::funcs::bsd44::ioctl(fd, ffi::os::FIONREAD, count as *mut libc::c_int)
+            })
+        }
     }
 }
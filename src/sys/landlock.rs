@@ -0,0 +1,200 @@
+//! Unprivileged filesystem sandboxing
+//!
+//! A Landlock ruleset lists the filesystem access rights a process wants to
+//! restrict, together with the hierarchies in which those rights remain
+//! allowed. Once `landlock_restrict_self` is called the restrictions apply to
+//! the calling thread and every process it later spawns.
+//!
+//! [Further reading](https://docs.kernel.org/userspace-api/landlock.html)
+use {Error, Result};
+use errno::Errno;
+use fcntl::Fd;
+use libc::{c_ulong, size_t};
+use std::{mem, ptr};
+use sys::syscall::{
+    syscall,
+    SYSLANDLOCKCREATERULESET,
+    SYSLANDLOCKADDRULE,
+    SYSLANDLOCKRESTRICTSELF,
+};
+
+mod ffi {
+    use libc::c_int;
+
+    pub const LANDLOCK_CREATE_RULESET_VERSION: u32 = 1 << 0;
+    pub const LANDLOCK_RULE_PATH_BENEATH: c_int = 1;
+    pub const PR_SET_NO_NEW_PRIVS: c_int = 38;
+
+    #[repr(C)]
+    pub struct landlock_ruleset_attr {
+        pub handled_access_fs: u64,
+    }
+
+    #[repr(C, packed)]
+    pub struct landlock_path_beneath_attr {
+        pub allowed_access: u64,
+        pub parent_fd: i32,
+    }
+
+    extern {
+        // operations on a process
+        // doc: http://man7.org/linux/man-pages/man2/prctl.2.html
+        pub fn prctl(option: c_int, ...) -> c_int;
+    }
+}
+
+bitflags!(
+    flags AccessFs: u64 {
+        const LANDLOCK_ACCESS_FS_EXECUTE     = 1 << 0,
+        const LANDLOCK_ACCESS_FS_WRITE_FILE  = 1 << 1,
+        const LANDLOCK_ACCESS_FS_READ_FILE   = 1 << 2,
+        const LANDLOCK_ACCESS_FS_READ_DIR    = 1 << 3,
+        const LANDLOCK_ACCESS_FS_REMOVE_DIR  = 1 << 4,
+        const LANDLOCK_ACCESS_FS_REMOVE_FILE = 1 << 5,
+        const LANDLOCK_ACCESS_FS_MAKE_CHAR   = 1 << 6,
+        const LANDLOCK_ACCESS_FS_MAKE_DIR    = 1 << 7,
+        const LANDLOCK_ACCESS_FS_MAKE_REG    = 1 << 8,
+        const LANDLOCK_ACCESS_FS_MAKE_SOCK   = 1 << 9,
+        const LANDLOCK_ACCESS_FS_MAKE_FIFO   = 1 << 10,
+        const LANDLOCK_ACCESS_FS_MAKE_BLOCK  = 1 << 11,
+        const LANDLOCK_ACCESS_FS_MAKE_SYM    = 1 << 12,
+        const LANDLOCK_ACCESS_FS_REFER       = 1 << 13, // Since ABI 2
+        const LANDLOCK_ACCESS_FS_TRUNCATE    = 1 << 14, // Since ABI 3
+        const LANDLOCK_ACCESS_FS_IOCTL_DEV   = 1 << 15  // Since ABI 5
+    }
+);
+
+impl AccessFs {
+    /// Returns the access rights understood by the given Landlock ABI
+    /// version, as reported by `landlock_abi_version`.
+    pub fn from_abi(abi: u32) -> AccessFs {
+        match abi {
+            0 => AccessFs::empty(),
+            1 => AccessFs::from_bits_truncate((1 << 13) - 1),
+            2 => AccessFs::from_bits_truncate((1 << 14) - 1),
+            3 | 4 => AccessFs::from_bits_truncate((1 << 15) - 1),
+            _ => AccessFs::all(),
+        }
+    }
+}
+
+/// Returns the highest Landlock ABI version supported by the running kernel.
+///
+/// Fails with `ENOSYS` if the kernel was built without Landlock and with
+/// `EOPNOTSUPP` if it was disabled at boot time.
+pub fn landlock_abi_version() -> Result<u32> {
+    let res = unsafe {
+        syscall(SYSLANDLOCKCREATERULESET,
+                ptr::null::<ffi::landlock_ruleset_attr>(),
+                0 as size_t,
+                ffi::LANDLOCK_CREATE_RULESET_VERSION)
+    };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res as u32)
+}
+
+/// Create a new ruleset restricting the access rights in `handled_access_fs`.
+///
+/// Every right must be known to the running kernel, otherwise the call fails
+/// with `EINVAL`; see `landlock_create_ruleset_best_effort` for a variant that
+/// degrades gracefully on older ABIs.
+pub fn landlock_create_ruleset(handled_access_fs: AccessFs) -> Result<Fd> {
+    let attr = ffi::landlock_ruleset_attr {
+        handled_access_fs: handled_access_fs.bits(),
+    };
+
+    let res = unsafe {
+        syscall(SYSLANDLOCKCREATERULESET,
+                &attr as *const ffi::landlock_ruleset_attr,
+                mem::size_of::<ffi::landlock_ruleset_attr>() as size_t,
+                0u32)
+    };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res)
+}
+
+/// Create a ruleset restricting the subset of `handled_access_fs` that the
+/// running kernel understands.
+///
+/// Returns the ruleset together with the access rights it actually handles,
+/// which callers should intersect with the rights passed to
+/// `landlock_add_rule`. Returns `Ok(None)` if Landlock is unavailable, so
+/// that callers can carry on unsandboxed.
+pub fn landlock_create_ruleset_best_effort(handled_access_fs: AccessFs) -> Result<Option<(Fd, AccessFs)>> {
+    let abi = match landlock_abi_version() {
+        Ok(abi) => abi,
+        Err(Error::Sys(Errno::ENOSYS)) | Err(Error::Sys(Errno::EOPNOTSUPP)) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let handled = handled_access_fs & AccessFs::from_abi(abi);
+
+    if handled.is_empty() {
+        return Ok(None);
+    }
+
+    let fd = try!(landlock_create_ruleset(handled));
+
+    Ok(Some((fd, handled)))
+}
+
+/// Allow `allowed_access` on the file hierarchy beneath `parent_fd`.
+///
+/// `parent_fd` is usually opened with `O_PATH` through `fcntl::open`.
+/// `allowed_access` must be a subset of the rights handled by the ruleset.
+pub fn landlock_add_rule(ruleset_fd: Fd, parent_fd: Fd, allowed_access: AccessFs) -> Result<()> {
+    let attr = ffi::landlock_path_beneath_attr {
+        allowed_access: allowed_access.bits(),
+        parent_fd: parent_fd,
+    };
+
+    let res = unsafe {
+        syscall(SYSLANDLOCKADDRULE,
+                ruleset_fd,
+                ffi::LANDLOCK_RULE_PATH_BENEATH,
+                &attr as *const ffi::landlock_path_beneath_attr,
+                0u32)
+    };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(())
+}
+
+/// Enforce the ruleset on the calling thread and its future children.
+///
+/// Unless the caller has `CAP_SYS_ADMIN`, `set_no_new_privs` must be called
+/// first or this fails with `EPERM`.
+pub fn landlock_restrict_self(ruleset_fd: Fd) -> Result<()> {
+    let res = unsafe { syscall(SYSLANDLOCKRESTRICTSELF, ruleset_fd, 0u32) };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(())
+}
+
+/// Prevent the calling thread and its children from gaining privileges
+/// through `execve`, as required by `landlock_restrict_self`.
+pub fn set_no_new_privs() -> Result<()> {
+    let res = unsafe {
+        ffi::prctl(ffi::PR_SET_NO_NEW_PRIVS, 1 as c_ulong, 0 as c_ulong, 0 as c_ulong, 0 as c_ulong)
+    };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+//! Anonymous memory-backed files
+//!
+//! A memfd behaves like a regular file on tmpfs that has no name in the
+//! filesystem, so it can be passed to other processes and mapped by them.
+//! Created with `MFD_ALLOW_SEALING`, it can be sealed through `fcntl` with
+//! `F_ADD_SEALS` so that a receiver can rely on its contents not changing.
+//!
+//! [Further reading](http://man7.org/linux/man-pages/man2/memfd_create.2.html)
+use {Error, Result, NixPath, AsExtStr};
+use errno::Errno;
+use fcntl::Fd;
+use libc::c_uint;
+
+mod ffi {
+    use libc::{c_char, c_int, c_uint};
+
+    extern {
+        pub fn memfd_create(name: *const c_char, flags: c_uint) -> c_int;
+    }
+}
+
+bitflags!(
+    flags MemFdCreateFlag: c_uint {
+        const MFD_CLOEXEC       = 0x0001,
+        /// Allow seals to be added; otherwise the file starts out sealed
+        /// with `F_SEAL_SEAL`
+        const MFD_ALLOW_SEALING = 0x0002,
+        /// Back the file with huge pages of the default size, or of the
+        /// size given by one of the `MFD_HUGE_*` values
+        const MFD_HUGETLB       = 0x0004,
+        /// Start out sealed against becoming executable (since Linux 6.3)
+        const MFD_NOEXEC_SEAL   = 0x0008,
+        /// Allow the file to become executable (since Linux 6.3)
+        const MFD_EXEC          = 0x0010,
+
+        // The huge page size as log2, in the bits from MFD_HUGE_SHIFT (26)
+        const MFD_HUGE_64KB     = 16 << 26,
+        const MFD_HUGE_512KB    = 19 << 26,
+        const MFD_HUGE_1MB      = 20 << 26,
+        const MFD_HUGE_2MB      = 21 << 26,
+        const MFD_HUGE_8MB      = 23 << 26,
+        const MFD_HUGE_16MB     = 24 << 26,
+        const MFD_HUGE_32MB     = 25 << 26,
+        const MFD_HUGE_256MB    = 28 << 26,
+        const MFD_HUGE_512MB    = 29 << 26,
+        const MFD_HUGE_1GB      = 30 << 26,
+        const MFD_HUGE_2GB      = 31 << 26,
+        const MFD_HUGE_16GB     = 34 << 26
+    }
+);
+
+/// Create an anonymous file and return a descriptor for it, open for
+/// reading and writing. `name` is only used for display, as the target of
+/// the `/proc/self/fd` link, and need not be unique.
+pub fn memfd_create<P: ?Sized + NixPath>(name: &P, flags: MemFdCreateFlag) -> Result<Fd> {
+    let res = try!(name.with_nix_path(|osstr| {
+        unsafe { ffi::memfd_create(osstr.as_ext_str(), flags.bits()) }
+    }));
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res)
+}
@@ -5,19 +5,37 @@ pub mod epoll;
 #[cfg(any(target_os = "macos", target_os = "ios"))]
 pub mod event;
 
+#[cfg(target_os = "linux")]
+pub mod fanotify;
+
 // TODO: switch from feature flags to conditional builds
 #[cfg(feature = "eventfd")]
 pub mod eventfd;
 
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub mod inotify;
+
 #[cfg(not(target_os = "ios"))]
 pub mod ioctl;
 
+#[cfg(target_os = "linux")]
+pub mod landlock;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub mod memfd;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub mod sendfile;
+
 pub mod signal;
 
 pub mod socket;
 
 pub mod stat;
 
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos", target_os = "ios"))]
+pub mod statvfs;
+
 #[cfg(any(target_os = "linux", target_os = "android"))]
 pub mod syscall;
 
@@ -34,3 +52,6 @@ pub mod mman;
 pub mod uio;
 
 pub mod time;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub mod xattr;
@@ -0,0 +1,36 @@
+//! Copying data between file descriptors inside the kernel
+//!
+//! [Further reading](http://man7.org/linux/man-pages/man2/sendfile.2.html)
+use {Error, Result};
+use errno::Errno;
+use fcntl::Fd;
+use libc::{off_t, size_t};
+use std::ptr;
+
+mod ffi {
+    use libc::{c_int, off_t, size_t, ssize_t};
+
+    extern {
+        pub fn sendfile(out_fd: c_int, in_fd: c_int, offset: *mut off_t, count: size_t) -> ssize_t;
+    }
+}
+
+/// Copy up to `count` bytes from `in_fd` to `out_fd` without passing them
+/// through userspace. `in_fd` must support `mmap`-like operations, so it is
+/// usually a regular file; `out_fd` may be any file, including a socket.
+///
+/// With `Some(offset)`, reading starts at `offset`, which is advanced past
+/// the bytes read, and the file offset of `in_fd` is left unchanged. With
+/// `None`, reading starts at and advances the file offset.
+///
+/// Returns the number of bytes written, zero at end of input.
+pub fn sendfile(out_fd: Fd, in_fd: Fd, offset: Option<&mut off_t>, count: usize) -> Result<usize> {
+    let offset = offset.map_or(ptr::null_mut(), |off| off as *mut off_t);
+    let res = unsafe { ffi::sendfile(out_fd, in_fd, offset, count as size_t) };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res as usize)
+}
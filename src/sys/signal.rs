@@ -48,6 +48,13 @@ pub use self::signal::sigset_t;
 // This doesn't always exist, but when it does, it's 7
 pub const SIGEMT: libc::c_int = 7;
 
+/// The address of a signal handler, or one of `SIG_DFL` and `SIG_IGN`
+#[allow(non_camel_case_types)]
+pub type sighandler_t = usize;
+
+pub const SIG_DFL: sighandler_t = 0;
+pub const SIG_IGN: sighandler_t = 1;
+
 #[cfg(any(all(target_os = "linux",
               any(target_arch = "x86",
                   target_arch = "x86_64",
@@ -109,7 +116,7 @@ pub mod signal {
     #[repr(C)]
     #[allow(missing_copy_implementations)]
     pub struct sigaction {
-        pub sa_handler: extern fn(libc::c_int),
+        pub sa_handler: super::sighandler_t,
         pub sa_mask: sigset_t,
         pub sa_flags: SockFlag,
         sa_restorer: *mut libc::c_void,
@@ -186,7 +193,7 @@ pub mod signal {
     #[repr(C)]
     pub struct sigaction {
         pub sa_flags: SockFlag,
-        pub sa_handler: extern fn(libc::c_int),
+        pub sa_handler: super::sighandler_t,
         pub sa_mask: sigset_t,
         sa_restorer: *mut libc::c_void,
         sa_resv: [libc::c_int; 1],
@@ -263,7 +270,7 @@ pub mod signal {
     #[repr(C)]
     #[allow(missing_copy_implementations)]
     pub struct sigaction {
-        pub sa_handler: extern fn(libc::c_int),
+        pub sa_handler: super::sighandler_t,
         sa_tramp: *mut libc::c_void,
         pub sa_mask: sigset_t,
         pub sa_flags: SockFlag,
@@ -272,7 +279,7 @@ pub mod signal {
     #[cfg(any(target_os = "freebsd", target_os = "dragonfly"))]
     #[repr(C)]
     pub struct sigaction {
-        pub sa_handler: extern fn(libc::c_int),
+        pub sa_handler: super::sighandler_t,
         pub sa_flags: SockFlag,
         pub sa_mask: sigset_t,
     }
@@ -342,12 +349,18 @@ pub struct SigAction {
 impl SigAction {
     pub fn new(handler: extern fn(libc::c_int), flags: SockFlag, mask: SigSet) -> SigAction {
         let mut s = unsafe { mem::uninitialized::<sigaction_t>() };
-        s.sa_handler = handler;
+        s.sa_handler = handler as sighandler_t;
         s.sa_flags = flags;
         s.sa_mask = mask.sigset;
 
         SigAction { sigaction: s }
     }
+
+    /// The installed handler, which is `SIG_DFL` or `SIG_IGN` for the
+    /// default and ignoring dispositions
+    pub fn handler(&self) -> sighandler_t {
+        self.sigaction.sa_handler
+    }
 }
 
 pub unsafe fn sigaction(signum: SigNum, sigaction: &SigAction) -> Result<SigAction> {
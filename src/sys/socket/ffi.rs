@@ -1,4 +1,4 @@
-use libc::{c_int, c_void, socklen_t};
+use libc::{c_int, c_void, sockaddr, socklen_t};
 pub use libc::{socket, listen, bind, accept, connect, setsockopt, sendto, recvfrom, getsockname, getpeername};
 
 extern {
@@ -15,4 +15,12 @@ extern {
         protocol:   c_int,
         sv:         *mut c_int
     ) -> c_int;
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn accept4(
+        sockfd:     c_int,
+        addr:       *mut sockaddr,
+        addrlen:    *mut socklen_t,
+        flags:      c_int
+    ) -> c_int;
 }
@@ -8,6 +8,7 @@ use fcntl::{fcntl, Fd, FD_CLOEXEC, O_NONBLOCK};
 use fcntl::FcntlArg::{F_SETFD, F_SETFL};
 use libc::{c_void, c_int, socklen_t, size_t};
 use std::{fmt, mem, ptr};
+use unistd::close;
 
 mod addr;
 mod consts;
@@ -172,27 +173,94 @@ pub fn accept(sockfd: Fd) -> Result<Fd> {
     Ok(res)
 }
 
-/// Accept a connection on a socket
+/// Accept a connection on a socket, with `flags` set on the new socket
+/// atomically where the kernel supports it
 ///
 /// [Further reading](http://man7.org/linux/man-pages/man2/accept.2.html)
 pub fn accept4(sockfd: Fd, flags: SockFlag) -> Result<Fd> {
-    accept4_polyfill(sockfd, flags)
+    unsafe { accept4_raw(sockfd, ptr::null_mut(), ptr::null_mut(), flags) }
+}
+
+/// Like `accept`, also returning the address of the peer
+pub fn accept_with_addr(sockfd: Fd) -> Result<(Fd, SockAddr)> {
+    unsafe {
+        let mut addr: sockaddr_storage = mem::zeroed();
+        let mut len = mem::size_of::<sockaddr_storage>() as socklen_t;
+
+        let res = ffi::accept(sockfd, &mut addr as *mut sockaddr_storage as *mut sockaddr, &mut len);
+
+        if res < 0 {
+            return Err(Error::last());
+        }
+
+        match sockaddr_storage_to_addr(&addr, len as usize) {
+            Ok(addr) => Ok((res, addr)),
+            Err(e) => {
+                let _ = close(res);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Like `accept4`, also returning the address of the peer
+pub fn accept4_with_addr(sockfd: Fd, flags: SockFlag) -> Result<(Fd, SockAddr)> {
+    unsafe {
+        let mut addr: sockaddr_storage = mem::zeroed();
+        let mut len = mem::size_of::<sockaddr_storage>() as socklen_t;
+
+        let res = try!(accept4_raw(sockfd, &mut addr as *mut sockaddr_storage as *mut sockaddr, &mut len, flags));
+
+        match sockaddr_storage_to_addr(&addr, len as usize) {
+            Ok(addr) => Ok((res, addr)),
+            Err(e) => {
+                let _ = close(res);
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+unsafe fn accept4_raw(sockfd: Fd, addr: *mut sockaddr, len: *mut socklen_t, flags: SockFlag) -> Result<Fd> {
+    if !features::accept4_available() {
+        return accept4_polyfill(sockfd, addr, len, flags);
+    }
+
+    let res = ffi::accept4(sockfd, addr, len, flags.bits());
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+unsafe fn accept4_raw(sockfd: Fd, addr: *mut sockaddr, len: *mut socklen_t, flags: SockFlag) -> Result<Fd> {
+    accept4_polyfill(sockfd, addr, len, flags)
 }
 
 #[inline]
-fn accept4_polyfill(sockfd: Fd, flags: SockFlag) -> Result<Fd> {
-    let res =  unsafe { ffi::accept(sockfd, ptr::null_mut(), ptr::null_mut()) };
+unsafe fn accept4_polyfill(sockfd: Fd, addr: *mut sockaddr, len: *mut socklen_t, flags: SockFlag) -> Result<Fd> {
+    let res = ffi::accept(sockfd, addr, len);
 
     if res < 0 {
         return Err(Error::Sys(Errno::last()));
     }
 
     if flags.contains(SOCK_CLOEXEC) {
-        try!(fcntl(res, F_SETFD(FD_CLOEXEC)));
+        if let Err(e) = fcntl(res, F_SETFD(FD_CLOEXEC)) {
+            let _ = close(res);
+            return Err(e);
+        }
     }
 
     if flags.contains(SOCK_NONBLOCK) {
-        try!(fcntl(res, F_SETFL(O_NONBLOCK)));
+        if let Err(e) = fcntl(res, F_SETFL(O_NONBLOCK)) {
+            let _ = close(res);
+            return Err(e);
+        }
     }
 
     Ok(res)
@@ -337,25 +405,36 @@ pub fn getsockname(fd: Fd) -> Result<SockAddr> {
     }
 }
 
+/// Fails with `EAFNOSUPPORT` for families `SockAddr` cannot represent
 pub unsafe fn sockaddr_storage_to_addr(
     addr: &sockaddr_storage,
     len: usize) -> Result<SockAddr> {
 
     match addr.ss_family as c_int {
         consts::AF_INET => {
-            assert!(len as usize == mem::size_of::<sockaddr_in>());
+            if len != mem::size_of::<sockaddr_in>() {
+                return Err(Error::invalid_argument());
+            }
             let ret = *(addr as *const _ as *const sockaddr_in);
             Ok(SockAddr::Inet(InetAddr::V4(ret)))
         }
         consts::AF_INET6 => {
-            assert!(len as usize == mem::size_of::<sockaddr_in6>());
+            if len != mem::size_of::<sockaddr_in6>() {
+                return Err(Error::invalid_argument());
+            }
             Ok(SockAddr::Inet(InetAddr::V6((*(addr as *const _ as *const sockaddr_in6)))))
         }
         consts::AF_UNIX => {
-            assert!(len as usize == mem::size_of::<sockaddr_un>());
-            Ok(SockAddr::Unix(UnixAddr(*(addr as *const _ as *const sockaddr_un))))
+            // Shorter for unnamed sockets and paths that do not fill
+            // sun_path, in which case the rest of `addr` may be garbage
+            if len > mem::size_of::<sockaddr_un>() {
+                return Err(Error::invalid_argument());
+            }
+            let mut ret: sockaddr_un = mem::zeroed();
+            ptr::copy_nonoverlapping(addr as *const _ as *const u8, &mut ret as *mut _ as *mut u8, len);
+            Ok(SockAddr::Unix(UnixAddr(ret)))
         }
-        af => panic!("unexpected address family {}", af),
+        _ => Err(Error::Sys(Errno::EAFNOSUPPORT)),
     }
 }
 
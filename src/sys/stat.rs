@@ -3,24 +3,44 @@ pub use libc::stat as FileStat;
 
 use {Error, Result, NixPath, AsExtStr,from_ffi};
 use errno::Errno;
-use fcntl::Fd;
+use fcntl::{Fd, DirFd, AtFlags};
 use libc::mode_t;
 use std::fmt;
 use std::mem;
+use sys::time::{TimeSpec, TimeVal};
+
+#[cfg(target_os = "linux")]
+pub use self::linux::*;
 
 mod ffi {
     use libc::{c_char, c_int, mode_t, dev_t};
     pub use libc::{stat, fstat};
+    use sys::time::{TimeSpec, TimeVal};
 
     extern {
+        pub fn lstat(pathname: *const c_char, buf: *mut stat) -> c_int;
+        pub fn fstatat(dirfd: c_int, pathname: *const c_char, buf: *mut stat, flags: c_int) -> c_int;
         pub fn mknod(pathname: *const c_char, mode: mode_t, dev: dev_t) -> c_int;
         pub fn umask(mask: mode_t) -> mode_t;
+        pub fn mknodat(dirfd: c_int, pathname: *const c_char, mode: mode_t, dev: dev_t) -> c_int;
+        pub fn mkdirat(dirfd: c_int, pathname: *const c_char, mode: mode_t) -> c_int;
+        pub fn mkfifoat(dirfd: c_int, pathname: *const c_char, mode: mode_t) -> c_int;
+        pub fn fchmodat(dirfd: c_int, pathname: *const c_char, mode: mode_t, flags: c_int) -> c_int;
+        pub fn chmod(pathname: *const c_char, mode: mode_t) -> c_int;
+        pub fn fchmod(fd: c_int, mode: mode_t) -> c_int;
+        pub fn utimensat(dirfd: c_int, pathname: *const c_char, times: *const TimeSpec, flags: c_int) -> c_int;
+        pub fn futimens(fd: c_int, times: *const TimeSpec) -> c_int;
+        pub fn utimes(filename: *const c_char, times: *const TimeVal) -> c_int;
+        pub fn lutimes(filename: *const c_char, times: *const TimeVal) -> c_int;
     }
 }
 
 bitflags!(
     flags SFlag: mode_t {
+        const S_IFMT   = 0o170000,
         const S_IFREG  = 0o100000,
+        const S_IFDIR  = 0o040000,
+        const S_IFLNK  = 0o120000,
         const S_IFCHR  = 0o020000,
         const S_IFBLK  = 0o060000,
         const S_IFIFO  = 0o010000,
@@ -66,12 +86,141 @@ pub fn mknod<P: ?Sized + NixPath>(path: &P, kind: SFlag, perm: Mode, dev: dev_t)
     from_ffi(res)
 }
 
+pub fn mknodat<P: ?Sized + NixPath>(dirfd: DirFd, path: &P, kind: SFlag, perm: Mode, dev: dev_t) -> Result<()> {
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe {
+            ffi::mknodat(dirfd.as_raw(), osstr.as_ext_str(), kind.bits | perm.bits() as mode_t, dev)
+        }
+    }));
+    from_ffi(res)
+}
+
+pub fn mkdirat<P: ?Sized + NixPath>(dirfd: DirFd, path: &P, mode: Mode) -> Result<()> {
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::mkdirat(dirfd.as_raw(), osstr.as_ext_str(), mode.bits() as mode_t) }
+    }));
+    from_ffi(res)
+}
+
+pub fn mkfifoat<P: ?Sized + NixPath>(dirfd: DirFd, path: &P, mode: Mode) -> Result<()> {
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::mkfifoat(dirfd.as_raw(), osstr.as_ext_str(), mode.bits() as mode_t) }
+    }));
+    from_ffi(res)
+}
+
+/// Change the permission bits of the file at `path`, following symbolic links
+pub fn chmod<P: ?Sized + NixPath>(path: &P, mode: Mode) -> Result<()> {
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::chmod(osstr.as_ext_str(), mode.bits() as mode_t) }
+    }));
+    from_ffi(res)
+}
+
+/// Change the permission bits of an open file
+pub fn fchmod(fd: Fd, mode: Mode) -> Result<()> {
+    let res = unsafe { ffi::fchmod(fd, mode.bits() as mode_t) };
+    from_ffi(res)
+}
+
+pub fn fchmodat<P: ?Sized + NixPath>(dirfd: DirFd, path: &P, mode: Mode, flags: AtFlags) -> Result<()> {
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::fchmodat(dirfd.as_raw(), osstr.as_ext_str(), mode.bits() as mode_t, flags.bits()) }
+    }));
+    from_ffi(res)
+}
+
+/// Passed as a timestamp to `utimensat` or `futimens` to set it to the
+/// current time.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub const UTIME_NOW: TimeSpec = TimeSpec { tv_sec: 0, tv_nsec: (1 << 30) - 1 };
+
+/// Passed as a timestamp to `utimensat` or `futimens` to leave it unchanged.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub const UTIME_OMIT: TimeSpec = TimeSpec { tv_sec: 0, tv_nsec: (1 << 30) - 2 };
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub const UTIME_NOW: TimeSpec = TimeSpec { tv_sec: 0, tv_nsec: -1 };
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub const UTIME_OMIT: TimeSpec = TimeSpec { tv_sec: 0, tv_nsec: -2 };
+
+/// Change the access and modification times of a file with nanosecond
+/// precision. Either time may be `UTIME_NOW` or `UTIME_OMIT`.
+///
+/// With `AT_SYMLINK_NOFOLLOW` the times of a symbolic link itself are
+/// changed.
+///
+/// [Further reading](http://man7.org/linux/man-pages/man2/utimensat.2.html)
+pub fn utimensat<P: ?Sized + NixPath>(dirfd: DirFd, path: &P, atime: &TimeSpec, mtime: &TimeSpec, flags: AtFlags) -> Result<()> {
+    let times = [*atime, *mtime];
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::utimensat(dirfd.as_raw(), osstr.as_ext_str(), times.as_ptr(), flags.bits()) }
+    }));
+    from_ffi(res)
+}
+
+/// Change the access and modification times of an open file with
+/// nanosecond precision. Either time may be `UTIME_NOW` or `UTIME_OMIT`.
+pub fn futimens(fd: Fd, atime: &TimeSpec, mtime: &TimeSpec) -> Result<()> {
+    let times = [*atime, *mtime];
+    let res = unsafe { ffi::futimens(fd, times.as_ptr()) };
+    from_ffi(res)
+}
+
+/// Change the access and modification times of a file with microsecond
+/// precision.
+pub fn utimes<P: ?Sized + NixPath>(path: &P, atime: &TimeVal, mtime: &TimeVal) -> Result<()> {
+    let times = [*atime, *mtime];
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::utimes(osstr.as_ext_str(), times.as_ptr()) }
+    }));
+    from_ffi(res)
+}
+
+/// Like `utimes`, but changes the times of a symbolic link itself rather
+/// than those of the file it refers to.
+pub fn lutimes<P: ?Sized + NixPath>(path: &P, atime: &TimeVal, mtime: &TimeVal) -> Result<()> {
+    let times = [*atime, *mtime];
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::lutimes(osstr.as_ext_str(), times.as_ptr()) }
+    }));
+    from_ffi(res)
+}
+
+/// Combine a major and minor device number into a `dev_t`, as expected by
+/// `mknod`.
 #[cfg(target_os = "linux")]
-const MINORBITS: usize = 20;
+pub fn mkdev(major: u64, minor: u64) -> dev_t {
+    ((major & 0xfffff000) << 32) | ((major & 0x00000fff) << 8) |
+    ((minor & 0xffffff00) << 12) | (minor & 0x000000ff)
+}
 
+/// Extract the major number of a device number.
 #[cfg(target_os = "linux")]
+pub fn major(dev: dev_t) -> u64 {
+    ((dev >> 32) & 0xfffff000) | ((dev >> 8) & 0x00000fff)
+}
+
+/// Extract the minor number of a device number.
+#[cfg(target_os = "linux")]
+pub fn minor(dev: dev_t) -> u64 {
+    ((dev >> 12) & 0xffffff00) | (dev & 0x000000ff)
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
 pub fn mkdev(major: u64, minor: u64) -> dev_t {
-    (major << MINORBITS) | minor
+    ((major << 24) | minor) as dev_t
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub fn major(dev: dev_t) -> u64 {
+    ((dev >> 24) & 0xff) as u64
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub fn minor(dev: dev_t) -> u64 {
+    (dev & 0xffffff) as u64
 }
 
 pub fn umask(mode: Mode) -> Mode {
@@ -104,3 +253,363 @@ pub fn fstat(fd: Fd) -> Result<FileStat> {
 
     Ok(dst)
 }
+
+pub fn lstat<P: ?Sized + NixPath>(path: &P) -> Result<FileStat> {
+    let mut dst = unsafe { mem::uninitialized() };
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe {
+            ffi::lstat(osstr.as_ext_str(), &mut dst as *mut FileStat)
+        }
+    }));
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(dst)
+}
+
+pub fn fstatat<P: ?Sized + NixPath>(dirfd: DirFd, path: &P, flags: AtFlags) -> Result<FileStat> {
+    let mut dst = unsafe { mem::uninitialized() };
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe {
+            ffi::fstatat(dirfd.as_raw(), osstr.as_ext_str(), &mut dst as *mut FileStat, flags.bits())
+        }
+    }));
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(dst)
+}
+
+/// The type of a file, decoded from the `S_IFMT` bits of its mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileType {
+    Regular,
+    Directory,
+    Symlink,
+    CharDevice,
+    BlockDevice,
+    Fifo,
+    Socket,
+}
+
+impl FileType {
+    pub fn from_mode(mode: mode_t) -> Option<FileType> {
+        let fmt = mode & S_IFMT.bits();
+
+        if fmt == S_IFREG.bits() {
+            Some(FileType::Regular)
+        } else if fmt == S_IFDIR.bits() {
+            Some(FileType::Directory)
+        } else if fmt == S_IFLNK.bits() {
+            Some(FileType::Symlink)
+        } else if fmt == S_IFCHR.bits() {
+            Some(FileType::CharDevice)
+        } else if fmt == S_IFBLK.bits() {
+            Some(FileType::BlockDevice)
+        } else if fmt == S_IFIFO.bits() {
+            Some(FileType::Fifo)
+        } else if fmt == S_IFSOCK.bits() {
+            Some(FileType::Socket)
+        } else {
+            None
+        }
+    }
+}
+
+/// A typed view of the `FileStat` returned by `stat` and friends.
+#[derive(Clone, Copy)]
+pub struct FileStatus {
+    st: FileStat,
+}
+
+impl FileStatus {
+    pub fn from_raw(st: FileStat) -> FileStatus {
+        FileStatus { st: st }
+    }
+
+    pub fn as_raw(&self) -> &FileStat {
+        &self.st
+    }
+
+    pub fn file_type(&self) -> Option<FileType> {
+        FileType::from_mode(self.st.st_mode as mode_t)
+    }
+
+    /// Returns the permission bits, including setuid, setgid and sticky
+    pub fn mode(&self) -> Mode {
+        Mode::from_bits_truncate(self.st.st_mode as mode_t)
+    }
+
+    pub fn dev(&self) -> dev_t {
+        self.st.st_dev as dev_t
+    }
+
+    /// Returns the device number for character and block devices
+    pub fn rdev(&self) -> dev_t {
+        self.st.st_rdev as dev_t
+    }
+
+    pub fn ino(&self) -> u64 {
+        self.st.st_ino as u64
+    }
+
+    pub fn nlink(&self) -> u64 {
+        self.st.st_nlink as u64
+    }
+
+    pub fn uid(&self) -> u32 {
+        self.st.st_uid as u32
+    }
+
+    pub fn gid(&self) -> u32 {
+        self.st.st_gid as u32
+    }
+
+    pub fn size(&self) -> i64 {
+        self.st.st_size as i64
+    }
+
+    pub fn blksize(&self) -> i64 {
+        self.st.st_blksize as i64
+    }
+
+    /// Returns the number of 512-byte blocks allocated
+    pub fn blocks(&self) -> i64 {
+        self.st.st_blocks as i64
+    }
+
+    pub fn atime(&self) -> TimeSpec {
+        TimeSpec { tv_sec: self.st.st_atime, tv_nsec: self.st.st_atime_nsec }
+    }
+
+    pub fn mtime(&self) -> TimeSpec {
+        TimeSpec { tv_sec: self.st.st_mtime, tv_nsec: self.st.st_mtime_nsec }
+    }
+
+    pub fn ctime(&self) -> TimeSpec {
+        TimeSpec { tv_sec: self.st.st_ctime, tv_nsec: self.st.st_ctime_nsec }
+    }
+}
+
+impl From<FileStat> for FileStatus {
+    fn from(st: FileStat) -> FileStatus {
+        FileStatus::from_raw(st)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use {Error, Result, NixPath, AsExtStr};
+    use errno::Errno;
+    use fcntl::{DirFd, AtFlags};
+    use libc::{c_uint, c_long, mode_t, time_t};
+    use std::mem;
+    use sys::syscall::{syscall, SYSSTATX};
+    use sys::time::TimeSpec;
+    use super::{FileType, Mode};
+
+    bitflags!(
+        flags StatxMask: u32 {
+            const STATX_TYPE        = 0x0001,
+            const STATX_MODE        = 0x0002,
+            const STATX_NLINK       = 0x0004,
+            const STATX_UID         = 0x0008,
+            const STATX_GID         = 0x0010,
+            const STATX_ATIME       = 0x0020,
+            const STATX_MTIME       = 0x0040,
+            const STATX_CTIME       = 0x0080,
+            const STATX_INO         = 0x0100,
+            const STATX_SIZE        = 0x0200,
+            const STATX_BLOCKS      = 0x0400,
+            const STATX_BASIC_STATS = 0x07ff,
+            const STATX_BTIME       = 0x0800,
+            const STATX_MNT_ID      = 0x1000, // Since Linux 5.8
+            const STATX_DIOALIGN    = 0x2000  // Since Linux 6.1
+        }
+    );
+
+    bitflags!(
+        flags StatxAttr: u64 {
+            const STATX_ATTR_COMPRESSED = 0x00000004,
+            const STATX_ATTR_IMMUTABLE  = 0x00000010,
+            const STATX_ATTR_APPEND     = 0x00000020,
+            const STATX_ATTR_NODUMP     = 0x00000040,
+            const STATX_ATTR_ENCRYPTED  = 0x00000800,
+            const STATX_ATTR_AUTOMOUNT  = 0x00001000,
+            const STATX_ATTR_MOUNT_ROOT = 0x00002000,
+            const STATX_ATTR_VERITY     = 0x00100000,
+            const STATX_ATTR_DAX        = 0x00200000
+        }
+    );
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct statx_timestamp {
+        tv_sec: i64,
+        tv_nsec: u32,
+        __reserved: i32,
+    }
+
+    /// Extended file status returned by `statx`. Only the fields in `mask()`
+    /// were filled in by the kernel.
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct Statx {
+        stx_mask: u32,
+        stx_blksize: u32,
+        stx_attributes: u64,
+        stx_nlink: u32,
+        stx_uid: u32,
+        stx_gid: u32,
+        stx_mode: u16,
+        __spare0: u16,
+        stx_ino: u64,
+        stx_size: u64,
+        stx_blocks: u64,
+        stx_attributes_mask: u64,
+        stx_atime: statx_timestamp,
+        stx_btime: statx_timestamp,
+        stx_ctime: statx_timestamp,
+        stx_mtime: statx_timestamp,
+        stx_rdev_major: u32,
+        stx_rdev_minor: u32,
+        stx_dev_major: u32,
+        stx_dev_minor: u32,
+        stx_mnt_id: u64,
+        stx_dio_mem_align: u32,
+        stx_dio_offset_align: u32,
+        __spare3: [u64; 12],
+    }
+
+    impl Statx {
+        pub fn mask(&self) -> StatxMask {
+            StatxMask::from_bits_truncate(self.stx_mask)
+        }
+
+        pub fn file_type(&self) -> Option<FileType> {
+            FileType::from_mode(self.stx_mode as mode_t)
+        }
+
+        pub fn mode(&self) -> Mode {
+            Mode::from_bits_truncate(self.stx_mode as mode_t)
+        }
+
+        pub fn nlink(&self) -> u32 {
+            self.stx_nlink
+        }
+
+        pub fn uid(&self) -> u32 {
+            self.stx_uid
+        }
+
+        pub fn gid(&self) -> u32 {
+            self.stx_gid
+        }
+
+        pub fn ino(&self) -> u64 {
+            self.stx_ino
+        }
+
+        pub fn size(&self) -> u64 {
+            self.stx_size
+        }
+
+        pub fn blksize(&self) -> u32 {
+            self.stx_blksize
+        }
+
+        pub fn blocks(&self) -> u64 {
+            self.stx_blocks
+        }
+
+        /// Returns the attributes set on the file. Only those in
+        /// `attributes_mask()` are supported by the filesystem.
+        pub fn attributes(&self) -> StatxAttr {
+            StatxAttr::from_bits_truncate(self.stx_attributes)
+        }
+
+        pub fn attributes_mask(&self) -> StatxAttr {
+            StatxAttr::from_bits_truncate(self.stx_attributes_mask)
+        }
+
+        pub fn atime(&self) -> TimeSpec {
+            timespec(&self.stx_atime)
+        }
+
+        /// Returns the creation time, if the filesystem records it
+        pub fn btime(&self) -> Option<TimeSpec> {
+            if self.mask().contains(STATX_BTIME) {
+                Some(timespec(&self.stx_btime))
+            } else {
+                None
+            }
+        }
+
+        pub fn ctime(&self) -> TimeSpec {
+            timespec(&self.stx_ctime)
+        }
+
+        pub fn mtime(&self) -> TimeSpec {
+            timespec(&self.stx_mtime)
+        }
+
+        pub fn rdev_major(&self) -> u32 {
+            self.stx_rdev_major
+        }
+
+        pub fn rdev_minor(&self) -> u32 {
+            self.stx_rdev_minor
+        }
+
+        pub fn dev_major(&self) -> u32 {
+            self.stx_dev_major
+        }
+
+        pub fn dev_minor(&self) -> u32 {
+            self.stx_dev_minor
+        }
+
+        /// Returns the id of the mount containing the file, as found in
+        /// `/proc/self/mountinfo`, if the kernel reported it
+        pub fn mnt_id(&self) -> Option<u64> {
+            if self.mask().contains(STATX_MNT_ID) {
+                Some(self.stx_mnt_id)
+            } else {
+                None
+            }
+        }
+    }
+
+    fn timespec(ts: &statx_timestamp) -> TimeSpec {
+        TimeSpec { tv_sec: ts.tv_sec as time_t, tv_nsec: ts.tv_nsec as c_long }
+    }
+
+    /// Get extended file status. `mask` selects the fields the caller is
+    /// interested in; the kernel may fill in more or fewer.
+    ///
+    /// [Further reading](http://man7.org/linux/man-pages/man2/statx.2.html)
+    pub fn statx<P: ?Sized + NixPath>(dirfd: DirFd, path: &P, flags: AtFlags, mask: StatxMask) -> Result<Statx> {
+        let mut dst: Statx = unsafe { mem::zeroed() };
+        let res = try!(path.with_nix_path(|osstr| {
+            unsafe {
+                syscall(SYSSTATX, dirfd.as_raw(), osstr.as_ext_str(), flags.bits(),
+                        mask.bits() as c_uint, &mut dst as *mut Statx)
+            }
+        }));
+
+        if res < 0 {
+            return Err(Error::Sys(Errno::last()));
+        }
+
+        Ok(dst)
+    }
+
+    #[test]
+    fn test_statx_size() {
+        assert_eq!(mem::size_of::<Statx>(), 256);
+    }
+}
@@ -0,0 +1,346 @@
+//! Filesystem statistics
+//!
+//! [Further reading](http://man7.org/linux/man-pages/man3/statvfs.3.html)
+use {Error, Result, NixPath, AsExtStr};
+use errno::Errno;
+use fcntl::Fd;
+use libc::c_ulong;
+use std::mem;
+
+pub use self::consts::*;
+
+#[cfg(target_os = "linux")]
+pub use self::linux::*;
+
+mod ffi {
+    use libc::{c_char, c_int};
+    use super::Statvfs;
+
+    extern {
+        pub fn statvfs(path: *const c_char, buf: *mut Statvfs) -> c_int;
+        pub fn fstatvfs(fd: c_int, buf: *mut Statvfs) -> c_int;
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod consts {
+    use libc::c_ulong;
+
+    bitflags!(
+        flags FsFlags: c_ulong {
+            const ST_RDONLY      = 1,
+            const ST_NOSUID      = 2,
+            const ST_NODEV       = 4,
+            const ST_NOEXEC      = 8,
+            const ST_SYNCHRONOUS = 16,
+            const ST_MANDLOCK    = 64,
+            const ST_WRITE       = 128,
+            const ST_APPEND      = 256,
+            const ST_IMMUTABLE   = 512,
+            const ST_NOATIME     = 1024,
+            const ST_NODIRATIME  = 2048,
+            const ST_RELATIME    = 4096
+        }
+    );
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+mod consts {
+    use libc::c_ulong;
+
+    bitflags!(
+        flags FsFlags: c_ulong {
+            const ST_RDONLY = 1,
+            const ST_NOSUID = 2
+        }
+    );
+}
+
+/// Filesystem statistics returned by `statvfs` and `fstatvfs`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Statvfs {
+    f_bsize: c_ulong,
+    f_frsize: c_ulong,
+    f_blocks: c_ulong,
+    f_bfree: c_ulong,
+    f_bavail: c_ulong,
+    f_files: c_ulong,
+    f_ffree: c_ulong,
+    f_favail: c_ulong,
+    f_fsid: c_ulong,
+    #[cfg(target_pointer_width = "32")]
+    __f_unused: ::libc::c_int,
+    f_flag: c_ulong,
+    f_namemax: c_ulong,
+    __f_spare: [::libc::c_int; 6],
+}
+
+/// Filesystem statistics returned by `statvfs` and `fstatvfs`.
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Statvfs {
+    f_bsize: c_ulong,
+    f_frsize: c_ulong,
+    f_blocks: ::libc::c_uint,
+    f_bfree: ::libc::c_uint,
+    f_bavail: ::libc::c_uint,
+    f_files: ::libc::c_uint,
+    f_ffree: ::libc::c_uint,
+    f_favail: ::libc::c_uint,
+    f_fsid: c_ulong,
+    f_flag: c_ulong,
+    f_namemax: c_ulong,
+}
+
+impl Statvfs {
+    /// Returns the preferred block size for I/O
+    pub fn block_size(&self) -> u64 {
+        self.f_bsize as u64
+    }
+
+    /// Returns the unit in which the block counts are expressed
+    pub fn fragment_size(&self) -> u64 {
+        self.f_frsize as u64
+    }
+
+    pub fn blocks(&self) -> u64 {
+        self.f_blocks as u64
+    }
+
+    pub fn blocks_free(&self) -> u64 {
+        self.f_bfree as u64
+    }
+
+    /// Returns the number of free blocks available to unprivileged users
+    pub fn blocks_available(&self) -> u64 {
+        self.f_bavail as u64
+    }
+
+    pub fn files(&self) -> u64 {
+        self.f_files as u64
+    }
+
+    pub fn files_free(&self) -> u64 {
+        self.f_ffree as u64
+    }
+
+    /// Returns the number of free inodes available to unprivileged users
+    pub fn files_available(&self) -> u64 {
+        self.f_favail as u64
+    }
+
+    pub fn filesystem_id(&self) -> u64 {
+        self.f_fsid as u64
+    }
+
+    pub fn flags(&self) -> FsFlags {
+        FsFlags::from_bits_truncate(self.f_flag)
+    }
+
+    pub fn name_max(&self) -> u64 {
+        self.f_namemax as u64
+    }
+
+    /// Returns the size of the filesystem in bytes
+    pub fn total_bytes(&self) -> u64 {
+        self.blocks() * self.fragment_size()
+    }
+
+    /// Returns the number of free bytes, including those reserved for root
+    pub fn free_bytes(&self) -> u64 {
+        self.blocks_free() * self.fragment_size()
+    }
+
+    /// Returns the number of bytes available to unprivileged users
+    pub fn available_bytes(&self) -> u64 {
+        self.blocks_available() * self.fragment_size()
+    }
+}
+
+/// Get statistics about the filesystem containing `path`
+pub fn statvfs<P: ?Sized + NixPath>(path: &P) -> Result<Statvfs> {
+    let mut dst: Statvfs = unsafe { mem::uninitialized() };
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::statvfs(osstr.as_ext_str(), &mut dst as *mut Statvfs) }
+    }));
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(dst)
+}
+
+/// Get statistics about the filesystem containing the open file `fd`
+pub fn fstatvfs(fd: Fd) -> Result<Statvfs> {
+    let mut dst: Statvfs = unsafe { mem::uninitialized() };
+    let res = unsafe { ffi::fstatvfs(fd, &mut dst as *mut Statvfs) };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(dst)
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use {Error, Result, NixPath, AsExtStr};
+    use errno::Errno;
+    use fcntl::Fd;
+    use libc::{c_int, c_long, c_ulong};
+    use std::mem;
+
+    mod ffi {
+        use libc::{c_char, c_int};
+        use super::Statfs;
+
+        extern {
+            pub fn statfs(path: *const c_char, buf: *mut Statfs) -> c_int;
+            pub fn fstatfs(fd: c_int, buf: *mut Statfs) -> c_int;
+        }
+    }
+
+    /// The type of a filesystem, as reported in `f_type` by `statfs`.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct FsType(pub c_long);
+
+    pub const ADFS_SUPER_MAGIC: FsType      = FsType(0xadf5);
+    pub const AUTOFS_SUPER_MAGIC: FsType    = FsType(0x0187);
+    pub const BPF_FS_MAGIC: FsType          = FsType(0xcafe4a11_u32 as c_long);
+    pub const BTRFS_SUPER_MAGIC: FsType     = FsType(0x9123683e_u32 as c_long);
+    pub const CGROUP_SUPER_MAGIC: FsType    = FsType(0x27e0eb);
+    pub const CGROUP2_SUPER_MAGIC: FsType   = FsType(0x63677270);
+    pub const DEBUGFS_MAGIC: FsType         = FsType(0x64626720);
+    pub const DEVPTS_SUPER_MAGIC: FsType    = FsType(0x1cd1);
+    pub const EXT2_SUPER_MAGIC: FsType      = FsType(0xef53);
+    pub const EXT3_SUPER_MAGIC: FsType      = FsType(0xef53);
+    pub const EXT4_SUPER_MAGIC: FsType      = FsType(0xef53);
+    pub const FUSE_SUPER_MAGIC: FsType      = FsType(0x65735546);
+    pub const HUGETLBFS_MAGIC: FsType       = FsType(0x958458f6_u32 as c_long);
+    pub const ISOFS_SUPER_MAGIC: FsType     = FsType(0x9660);
+    pub const MSDOS_SUPER_MAGIC: FsType     = FsType(0x4d44);
+    pub const NFS_SUPER_MAGIC: FsType       = FsType(0x6969);
+    pub const NSFS_MAGIC: FsType            = FsType(0x6e736673);
+    pub const OVERLAYFS_SUPER_MAGIC: FsType = FsType(0x794c7630);
+    pub const PROC_SUPER_MAGIC: FsType      = FsType(0x9fa0);
+    pub const RAMFS_MAGIC: FsType           = FsType(0x858458f6_u32 as c_long);
+    pub const SQUASHFS_MAGIC: FsType        = FsType(0x73717368);
+    pub const SYSFS_MAGIC: FsType           = FsType(0x62656572);
+    pub const TMPFS_MAGIC: FsType           = FsType(0x01021994);
+    pub const XFS_SUPER_MAGIC: FsType       = FsType(0x58465342);
+    pub const ZFS_SUPER_MAGIC: FsType       = FsType(0x2fc12fc1);
+
+    /// Filesystem statistics returned by `statfs` and `fstatfs`.
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct Statfs {
+        f_type: c_long,
+        f_bsize: c_long,
+        f_blocks: c_ulong,
+        f_bfree: c_ulong,
+        f_bavail: c_ulong,
+        f_files: c_ulong,
+        f_ffree: c_ulong,
+        f_fsid: [c_int; 2],
+        f_namelen: c_long,
+        f_frsize: c_long,
+        f_flags: c_long,
+        f_spare: [c_long; 4],
+    }
+
+    impl Statfs {
+        pub fn filesystem_type(&self) -> FsType {
+            // f_type is an unsigned magic number, sign-extended on 32-bit
+            FsType(self.f_type & 0xffffffff_u32 as c_long)
+        }
+
+        pub fn block_size(&self) -> u64 {
+            self.f_bsize as u64
+        }
+
+        /// The unit `blocks`, `blocks_free` and `blocks_available` count in
+        pub fn fragment_size(&self) -> u64 {
+            self.f_frsize as u64
+        }
+
+        pub fn blocks(&self) -> u64 {
+            self.f_blocks as u64
+        }
+
+        pub fn blocks_free(&self) -> u64 {
+            self.f_bfree as u64
+        }
+
+        pub fn blocks_available(&self) -> u64 {
+            self.f_bavail as u64
+        }
+
+        pub fn files(&self) -> u64 {
+            self.f_files as u64
+        }
+
+        pub fn files_free(&self) -> u64 {
+            self.f_ffree as u64
+        }
+
+        pub fn name_max(&self) -> u64 {
+            self.f_namelen as u64
+        }
+
+        pub fn flags(&self) -> super::FsFlags {
+            super::FsFlags::from_bits_truncate(self.f_flags as c_ulong)
+        }
+
+        /// Returns the size of the filesystem in bytes
+        pub fn total_bytes(&self) -> u64 {
+            self.blocks() * self.fragment_size()
+        }
+
+        /// Returns the number of bytes available to unprivileged users
+        pub fn available_bytes(&self) -> u64 {
+            self.blocks_available() * self.fragment_size()
+        }
+    }
+
+    /// Get statistics about the filesystem containing `path`
+    pub fn statfs<P: ?Sized + NixPath>(path: &P) -> Result<Statfs> {
+        let mut dst: Statfs = unsafe { mem::uninitialized() };
+        let res = try!(path.with_nix_path(|osstr| {
+            unsafe { ffi::statfs(osstr.as_ext_str(), &mut dst as *mut Statfs) }
+        }));
+
+        if res < 0 {
+            return Err(Error::Sys(Errno::last()));
+        }
+
+        Ok(dst)
+    }
+
+    /// Get statistics about the filesystem containing the open file `fd`
+    pub fn fstatfs(fd: Fd) -> Result<Statfs> {
+        let mut dst: Statfs = unsafe { mem::uninitialized() };
+        let res = unsafe { ffi::fstatfs(fd, &mut dst as *mut Statfs) };
+
+        if res < 0 {
+            return Err(Error::Sys(Errno::last()));
+        }
+
+        Ok(dst)
+    }
+
+    #[test]
+    pub fn test_size_of_statfs() {
+        use nixtest;
+        nixtest::assert_size_of::<Statfs>("statfs");
+    }
+}
+
+#[test]
+pub fn test_size_of_statvfs() {
+    use nixtest;
+    nixtest::assert_size_of::<Statvfs>("statvfs");
+}
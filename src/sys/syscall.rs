@@ -11,6 +11,16 @@ mod arch {
     pub type Syscall = c_long;
 
     pub static SYSPIVOTROOT: Syscall = 155;
+
+    pub static SYSLANDLOCKCREATERULESET: Syscall = 444;
+    pub static SYSLANDLOCKADDRULE: Syscall = 445;
+    pub static SYSLANDLOCKRESTRICTSELF: Syscall = 446;
+
+    pub static SYSOPENAT2: Syscall = 437;
+
+    pub static SYSSTATX: Syscall = 332;
+
+    pub static SYSRENAMEAT2: Syscall = 316;
 }
 
 #[cfg(target_arch = "x86")]
@@ -20,6 +30,16 @@ mod arch {
     pub type Syscall = c_long;
 
     pub static SYSPIVOTROOT: Syscall = 217;
+
+    pub static SYSLANDLOCKCREATERULESET: Syscall = 444;
+    pub static SYSLANDLOCKADDRULE: Syscall = 445;
+    pub static SYSLANDLOCKRESTRICTSELF: Syscall = 446;
+
+    pub static SYSOPENAT2: Syscall = 437;
+
+    pub static SYSSTATX: Syscall = 383;
+
+    pub static SYSRENAMEAT2: Syscall = 353;
 }
 
 #[cfg(target_arch = "arm")]
@@ -29,6 +49,16 @@ mod arch {
     pub type Syscall = c_long;
 
     pub static SYSPIVOTROOT: Syscall = 218;
+
+    pub static SYSLANDLOCKCREATERULESET: Syscall = 444;
+    pub static SYSLANDLOCKADDRULE: Syscall = 445;
+    pub static SYSLANDLOCKRESTRICTSELF: Syscall = 446;
+
+    pub static SYSOPENAT2: Syscall = 437;
+
+    pub static SYSSTATX: Syscall = 397;
+
+    pub static SYSRENAMEAT2: Syscall = 382;
 }
 
 
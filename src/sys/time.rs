@@ -1,5 +1,5 @@
 use std::{fmt, ops};
-use libc::{time_t, suseconds_t};
+use libc::{time_t, suseconds_t, c_long};
 
 #[repr(C)]
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -173,6 +173,53 @@ impl fmt::Display for TimeVal {
     }
 }
 
+/// A time value with nanosecond precision, as used by file timestamps.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct TimeSpec {
+    pub tv_sec: time_t,
+    pub tv_nsec: c_long,
+}
+
+const NANOS_PER_SEC: i64 = 1_000_000_000;
+
+impl TimeSpec {
+    #[inline]
+    pub fn zero() -> TimeSpec {
+        TimeSpec::nanoseconds(0)
+    }
+
+    #[inline]
+    pub fn seconds(seconds: i64) -> TimeSpec {
+        TimeSpec { tv_sec: seconds as time_t, tv_nsec: 0 }
+    }
+
+    /// Makes a new `TimeSpec` with given number of nanoseconds.
+    #[inline]
+    pub fn nanoseconds(nanoseconds: i64) -> TimeSpec {
+        let (secs, nanos) = div_mod_floor_64(nanoseconds, NANOS_PER_SEC);
+        TimeSpec { tv_sec: secs as time_t, tv_nsec: nanos as c_long }
+    }
+
+    pub fn num_seconds(&self) -> i64 {
+        if self.tv_sec < 0 && self.tv_nsec > 0 {
+            (self.tv_sec + 1) as i64
+        } else {
+            self.tv_sec as i64
+        }
+    }
+
+    pub fn num_nanoseconds(&self) -> i64 {
+        let secs = self.num_seconds() * NANOS_PER_SEC;
+        let nsec = if self.tv_sec < 0 && self.tv_nsec > 0 {
+            self.tv_nsec as i64 - NANOS_PER_SEC
+        } else {
+            self.tv_nsec as i64
+        };
+        secs + nsec
+    }
+}
+
 #[inline]
 fn div_mod_floor_64(this: i64, other: i64) -> (i64, i64) {
     (div_floor_64(this, other), mod_floor_64(this, other))
@@ -203,7 +250,7 @@ fn div_rem_64(this: i64, other: i64) -> (i64, i64) {
 
 #[cfg(test)]
 mod test {
-    use super::TimeVal;
+    use super::{TimeVal, TimeSpec};
 
     #[test]
     pub fn test_time_val() {
@@ -229,4 +276,17 @@ mod test {
         assert_eq!(TimeVal::microseconds(42).to_string(), "0.000042 seconds");
         assert_eq!(TimeVal::seconds(-86401).to_string(), "-86401 seconds");
     }
+
+    #[test]
+    pub fn test_time_spec() {
+        let t = TimeSpec::nanoseconds(1_500_000_000);
+        assert_eq!(t, TimeSpec { tv_sec: 1, tv_nsec: 500_000_000 });
+        assert_eq!(t.num_seconds(), 1);
+        assert_eq!(t.num_nanoseconds(), 1_500_000_000);
+
+        let t = TimeSpec::nanoseconds(-1_500_000_000);
+        assert_eq!(t, TimeSpec { tv_sec: -2, tv_nsec: 500_000_000 });
+        assert_eq!(t.num_seconds(), -1);
+        assert_eq!(t.num_nanoseconds(), -1_500_000_000);
+    }
 }
@@ -4,12 +4,12 @@
 use {Result, Error};
 use errno::Errno;
 use fcntl::Fd;
-use libc::{c_int, c_void, size_t};
+use libc::{c_int, c_void, off_t, size_t};
 use std::marker::PhantomData;
 
 mod ffi {
     use super::IoVec;
-    use libc::{ssize_t, c_int};
+    use libc::{ssize_t, c_int, off_t};
     use fcntl::Fd;
 
     extern {
@@ -20,9 +20,39 @@ mod ffi {
         // vectorized version of read
         // doc: http://man7.org/linux/man-pages/man2/readv.2.html
         pub fn readv(fd: Fd, iov: *const IoVec<&mut [u8]>, iovcnt: c_int) -> ssize_t;
+
+        // vectorized versions of pwrite and pread
+        // doc: http://man7.org/linux/man-pages/man2/preadv.2.html
+        #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+        pub fn pwritev(fd: Fd, iov: *const IoVec<&[u8]>, iovcnt: c_int, offset: off_t) -> ssize_t;
+        #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+        pub fn preadv(fd: Fd, iov: *const IoVec<&mut [u8]>, iovcnt: c_int, offset: off_t) -> ssize_t;
+
+        // like pwritev and preadv, with per-call flags
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        pub fn pwritev2(fd: Fd, iov: *const IoVec<&[u8]>, iovcnt: c_int, offset: off_t, flags: c_int) -> ssize_t;
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        pub fn preadv2(fd: Fd, iov: *const IoVec<&mut [u8]>, iovcnt: c_int, offset: off_t, flags: c_int) -> ssize_t;
     }
 }
 
+#[cfg(any(target_os = "linux", target_os = "android"))]
+bitflags!(
+    flags ReadWriteFlags: c_int {
+        /// High priority request, polling for completion if supported
+        const RWF_HIPRI  = 0x00000001,
+        /// Per-write equivalent of `O_DSYNC`
+        const RWF_DSYNC  = 0x00000002,
+        /// Per-write equivalent of `O_SYNC`
+        const RWF_SYNC   = 0x00000004,
+        /// Fail with `EAGAIN` instead of waiting for data to become
+        /// available (reads only)
+        const RWF_NOWAIT = 0x00000008,
+        /// Per-write equivalent of `O_APPEND`
+        const RWF_APPEND = 0x00000010
+    }
+);
+
 pub fn writev(fd: Fd, iov: &[IoVec<&[u8]>]) -> Result<usize> {
     let res = unsafe { ffi::writev(fd, iov.as_ptr(), iov.len() as c_int) };
 
@@ -42,6 +72,56 @@ pub fn readv(fd: Fd, iov: &mut [IoVec<&mut [u8]>]) -> Result<usize> {
     return Ok(res as usize)
 }
 
+/// Like `writev`, but writes at `offset` and leaves the file offset unchanged
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+pub fn pwritev(fd: Fd, iov: &[IoVec<&[u8]>], offset: off_t) -> Result<usize> {
+    let res = unsafe { ffi::pwritev(fd, iov.as_ptr(), iov.len() as c_int, offset) };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res as usize)
+}
+
+/// Like `readv`, but reads from `offset` and leaves the file offset unchanged
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+pub fn preadv(fd: Fd, iov: &mut [IoVec<&mut [u8]>], offset: off_t) -> Result<usize> {
+    let res = unsafe { ffi::preadv(fd, iov.as_ptr(), iov.len() as c_int, offset) };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res as usize)
+}
+
+/// Like `pwritev`, with `flags` applied to this call only. An `offset` of -1
+/// writes at, and advances, the file offset.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn pwritev2(fd: Fd, iov: &[IoVec<&[u8]>], offset: off_t, flags: ReadWriteFlags) -> Result<usize> {
+    let res = unsafe { ffi::pwritev2(fd, iov.as_ptr(), iov.len() as c_int, offset, flags.bits()) };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res as usize)
+}
+
+/// Like `preadv`, with `flags` applied to this call only. An `offset` of -1
+/// reads from, and advances, the file offset.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn preadv2(fd: Fd, iov: &mut [IoVec<&mut [u8]>], offset: off_t, flags: ReadWriteFlags) -> Result<usize> {
+    let res = unsafe { ffi::preadv2(fd, iov.as_ptr(), iov.len() as c_int, offset, flags.bits()) };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res as usize)
+}
+
 #[repr(C)]
 pub struct IoVec<T> {
     iov_base: *mut c_void,
@@ -0,0 +1,208 @@
+//! Extended file attributes
+//!
+//! Each function comes in three variants: one taking a path, an `l*` one that
+//! operates on a symbolic link itself rather than the file it refers to, and
+//! an `f*` one taking an open file descriptor. Attribute names carry their
+//! namespace, e.g. `user.mime_type`.
+//!
+//! [Further reading](http://man7.org/linux/man-pages/man7/xattr.7.html)
+use {Error, Result, NixPath, AsExtStr, from_ffi};
+use errno::Errno;
+use fcntl::Fd;
+use libc::{c_void, size_t, ssize_t};
+use std::ffi::OsString;
+use std::os::unix::ffi::OsStringExt;
+use std::ptr;
+
+mod ffi {
+    use libc::{c_char, c_int, c_void, size_t, ssize_t};
+
+    extern {
+        pub fn getxattr(path: *const c_char, name: *const c_char, value: *mut c_void, size: size_t) -> ssize_t;
+        pub fn lgetxattr(path: *const c_char, name: *const c_char, value: *mut c_void, size: size_t) -> ssize_t;
+        pub fn fgetxattr(fd: c_int, name: *const c_char, value: *mut c_void, size: size_t) -> ssize_t;
+
+        pub fn setxattr(path: *const c_char, name: *const c_char, value: *const c_void, size: size_t, flags: c_int) -> c_int;
+        pub fn lsetxattr(path: *const c_char, name: *const c_char, value: *const c_void, size: size_t, flags: c_int) -> c_int;
+        pub fn fsetxattr(fd: c_int, name: *const c_char, value: *const c_void, size: size_t, flags: c_int) -> c_int;
+
+        pub fn removexattr(path: *const c_char, name: *const c_char) -> c_int;
+        pub fn lremovexattr(path: *const c_char, name: *const c_char) -> c_int;
+        pub fn fremovexattr(fd: c_int, name: *const c_char) -> c_int;
+
+        pub fn listxattr(path: *const c_char, list: *mut c_char, size: size_t) -> ssize_t;
+        pub fn llistxattr(path: *const c_char, list: *mut c_char, size: size_t) -> ssize_t;
+        pub fn flistxattr(fd: c_int, list: *mut c_char, size: size_t) -> ssize_t;
+    }
+}
+
+bitflags!(
+    flags XattrFlags: ::libc::c_int {
+        /// Fail with `EEXIST` if the attribute already exists
+        const XATTR_CREATE  = 1,
+        /// Fail with `ENODATA` if the attribute does not exist
+        const XATTR_REPLACE = 2
+    }
+);
+
+/// Get the value of the attribute `name` of the file at `path`
+pub fn getxattr<P: ?Sized + NixPath, N: ?Sized + NixPath>(path: &P, name: &N) -> Result<Vec<u8>> {
+    try!(try!(path.with_nix_path(|path| name.with_nix_path(|name| {
+        fetch(|buf, len| unsafe { ffi::getxattr(path.as_ext_str(), name.as_ext_str(), buf, len) })
+    }))))
+}
+
+/// Like `getxattr`, but does not follow a symbolic link at `path`
+pub fn lgetxattr<P: ?Sized + NixPath, N: ?Sized + NixPath>(path: &P, name: &N) -> Result<Vec<u8>> {
+    try!(try!(path.with_nix_path(|path| name.with_nix_path(|name| {
+        fetch(|buf, len| unsafe { ffi::lgetxattr(path.as_ext_str(), name.as_ext_str(), buf, len) })
+    }))))
+}
+
+/// Get the value of the attribute `name` of an open file
+pub fn fgetxattr<N: ?Sized + NixPath>(fd: Fd, name: &N) -> Result<Vec<u8>> {
+    try!(name.with_nix_path(|name| {
+        fetch(|buf, len| unsafe { ffi::fgetxattr(fd, name.as_ext_str(), buf, len) })
+    }))
+}
+
+/// Set the attribute `name` of the file at `path` to `value`. With empty
+/// `flags` the attribute is created or replaced as needed.
+pub fn setxattr<P: ?Sized + NixPath, N: ?Sized + NixPath>(path: &P, name: &N, value: &[u8], flags: XattrFlags) -> Result<()> {
+    let res = try!(try!(path.with_nix_path(|path| name.with_nix_path(|name| unsafe {
+        ffi::setxattr(path.as_ext_str(), name.as_ext_str(),
+                      value.as_ptr() as *const c_void, value.len() as size_t, flags.bits())
+    }))));
+    from_ffi(res)
+}
+
+/// Like `setxattr`, but does not follow a symbolic link at `path`
+pub fn lsetxattr<P: ?Sized + NixPath, N: ?Sized + NixPath>(path: &P, name: &N, value: &[u8], flags: XattrFlags) -> Result<()> {
+    let res = try!(try!(path.with_nix_path(|path| name.with_nix_path(|name| unsafe {
+        ffi::lsetxattr(path.as_ext_str(), name.as_ext_str(),
+                       value.as_ptr() as *const c_void, value.len() as size_t, flags.bits())
+    }))));
+    from_ffi(res)
+}
+
+/// Set the attribute `name` of an open file to `value`
+pub fn fsetxattr<N: ?Sized + NixPath>(fd: Fd, name: &N, value: &[u8], flags: XattrFlags) -> Result<()> {
+    let res = try!(name.with_nix_path(|name| unsafe {
+        ffi::fsetxattr(fd, name.as_ext_str(),
+                       value.as_ptr() as *const c_void, value.len() as size_t, flags.bits())
+    }));
+    from_ffi(res)
+}
+
+/// Remove the attribute `name` from the file at `path`
+pub fn removexattr<P: ?Sized + NixPath, N: ?Sized + NixPath>(path: &P, name: &N) -> Result<()> {
+    let res = try!(try!(path.with_nix_path(|path| name.with_nix_path(|name| unsafe {
+        ffi::removexattr(path.as_ext_str(), name.as_ext_str())
+    }))));
+    from_ffi(res)
+}
+
+/// Like `removexattr`, but does not follow a symbolic link at `path`
+pub fn lremovexattr<P: ?Sized + NixPath, N: ?Sized + NixPath>(path: &P, name: &N) -> Result<()> {
+    let res = try!(try!(path.with_nix_path(|path| name.with_nix_path(|name| unsafe {
+        ffi::lremovexattr(path.as_ext_str(), name.as_ext_str())
+    }))));
+    from_ffi(res)
+}
+
+/// Remove the attribute `name` from an open file
+pub fn fremovexattr<N: ?Sized + NixPath>(fd: Fd, name: &N) -> Result<()> {
+    let res = try!(name.with_nix_path(|name| unsafe {
+        ffi::fremovexattr(fd, name.as_ext_str())
+    }));
+    from_ffi(res)
+}
+
+/// List the names of the attributes of the file at `path`. Only attributes
+/// the caller may access are listed.
+pub fn listxattr<P: ?Sized + NixPath>(path: &P) -> Result<XattrNames> {
+    let list = try!(try!(path.with_nix_path(|path| {
+        fetch(|buf, len| unsafe { ffi::listxattr(path.as_ext_str(), buf as *mut _, len) })
+    })));
+    Ok(XattrNames::new(list))
+}
+
+/// Like `listxattr`, but does not follow a symbolic link at `path`
+pub fn llistxattr<P: ?Sized + NixPath>(path: &P) -> Result<XattrNames> {
+    let list = try!(try!(path.with_nix_path(|path| {
+        fetch(|buf, len| unsafe { ffi::llistxattr(path.as_ext_str(), buf as *mut _, len) })
+    })));
+    Ok(XattrNames::new(list))
+}
+
+/// List the names of the attributes of an open file
+pub fn flistxattr(fd: Fd) -> Result<XattrNames> {
+    let list = try!(fetch(|buf, len| unsafe { ffi::flistxattr(fd, buf as *mut _, len) }));
+    Ok(XattrNames::new(list))
+}
+
+/// An iterator over the attribute names returned by `listxattr`.
+pub struct XattrNames {
+    list: Vec<u8>,
+    pos: usize,
+}
+
+impl XattrNames {
+    fn new(list: Vec<u8>) -> XattrNames {
+        XattrNames { list: list, pos: 0 }
+    }
+}
+
+impl Iterator for XattrNames {
+    type Item = OsString;
+
+    fn next(&mut self) -> Option<OsString> {
+        let rest = &self.list[self.pos..];
+
+        if rest.is_empty() {
+            return None;
+        }
+
+        // Names are NUL-terminated and packed back to back
+        let len = rest.iter().position(|b| *b == 0).unwrap_or(rest.len());
+        self.pos += len + 1;
+
+        Some(OsString::from_vec(rest[..len].to_vec()))
+    }
+}
+
+// Asks for the size first, then fetches into a buffer of that size. The
+// value may grow in between, in which case the call fails with ERANGE and
+// both steps are repeated. An empty value is not fetched at all, as a
+// zero-sized fetch would be another size query.
+fn fetch<F>(f: F) -> Result<Vec<u8>>
+        where F: Fn(*mut c_void, size_t) -> ssize_t {
+    loop {
+        let size = f(ptr::null_mut(), 0);
+
+        if size < 0 {
+            return Err(Error::Sys(Errno::last()));
+        }
+
+        if size == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut buf: Vec<u8> = Vec::with_capacity(size as usize);
+        let res = f(buf.as_mut_ptr() as *mut c_void, size as size_t);
+
+        if res < 0 {
+            match Errno::last() {
+                Errno::ERANGE => continue,
+                errno => return Err(Error::Sys(errno)),
+            }
+        }
+
+        if res as usize > buf.capacity() {
+            continue;
+        }
+
+        unsafe { buf.set_len(res as usize) };
+        return Ok(buf);
+    }
+}
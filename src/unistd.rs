@@ -2,18 +2,23 @@
 //!
 use {Error, Result, NixPath, AsExtStr, from_ffi};
 use errno::Errno;
-use fcntl::{fcntl, Fd, OFlag, O_NONBLOCK, O_CLOEXEC, FD_CLOEXEC};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use features;
+use fcntl::{fcntl, Fd, DirFd, AtFlags, OFlag, O_NONBLOCK, O_CLOEXEC, FD_CLOEXEC};
 use fcntl::FcntlArg::{F_SETFD, F_SETFL};
-use libc::{c_char, c_void, c_int, size_t, pid_t, off_t};
+use libc::{c_char, c_void, c_int, size_t, ssize_t, pid_t, off_t, mode_t, uid_t, gid_t};
 use std::{mem, ptr};
-use std::ffi::CString;
+use std::ffi::{CString, OsString};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::PathBuf;
+use sys::stat::Mode;
 
 #[cfg(any(target_os = "linux", target_os = "android"))]
 pub use self::linux::*;
 
 mod ffi {
-    use libc::{c_char, c_int, size_t};
-    pub use libc::{close, read, write, pipe, ftruncate, unlink};
+    use libc::{c_char, c_int, c_void, off_t, mode_t, size_t, ssize_t, uid_t, gid_t};
+    pub use libc::{close, read, write, pipe, ftruncate, unlink, lseek};
     pub use libc::funcs::posix88::unistd::fork;
 
     extern {
@@ -21,10 +26,72 @@ mod ffi {
         // doc: http://man7.org/linux/man-pages/man2/dup.2.html
         pub fn dup(oldfd: c_int) -> c_int;
         pub fn dup2(oldfd: c_int, newfd: c_int) -> c_int;
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        pub fn dup3(oldfd: c_int, newfd: c_int, flags: c_int) -> c_int;
 
         // change working directory
         // doc: http://man7.org/linux/man-pages/man2/chdir.2.html
         pub fn chdir(path: *const c_char) -> c_int;
+        pub fn fchdir(fd: c_int) -> c_int;
+
+        // get current working directory
+        // doc: http://man7.org/linux/man-pages/man3/getcwd.3.html
+        pub fn getcwd(buf: *mut c_char, size: size_t) -> *mut c_char;
+
+        // create and remove directories
+        // doc: http://man7.org/linux/man-pages/man2/mkdir.2.html
+        pub fn mkdir(pathname: *const c_char, mode: mode_t) -> c_int;
+        pub fn rmdir(pathname: *const c_char) -> c_int;
+
+        // change the name or location of a file
+        // doc: http://man7.org/linux/man-pages/man2/rename.2.html
+        pub fn rename(oldpath: *const c_char, newpath: *const c_char) -> c_int;
+
+        // make a new name for a file
+        // doc: http://man7.org/linux/man-pages/man2/link.2.html
+        pub fn link(oldpath: *const c_char, newpath: *const c_char) -> c_int;
+
+        // make a new name for a file
+        // doc: http://man7.org/linux/man-pages/man2/symlink.2.html
+        pub fn symlink(target: *const c_char, linkpath: *const c_char) -> c_int;
+
+        // read the value of a symbolic link
+        // doc: http://man7.org/linux/man-pages/man2/readlink.2.html
+        pub fn readlink(pathname: *const c_char, buf: *mut c_char, bufsiz: size_t) -> ssize_t;
+
+        // create a unique temporary file or directory
+        // doc: http://man7.org/linux/man-pages/man3/mkstemp.3.html
+        pub fn mkstemp(template: *mut c_char) -> c_int;
+        pub fn mkostemp(template: *mut c_char, flags: c_int) -> c_int;
+        pub fn mkstemps(template: *mut c_char, suffixlen: c_int) -> c_int;
+        // doc: http://man7.org/linux/man-pages/man3/mkdtemp.3.html
+        pub fn mkdtemp(template: *mut c_char) -> *mut c_char;
+
+        // make a FIFO special file
+        // doc: http://man7.org/linux/man-pages/man3/mkfifo.3.html
+        pub fn mkfifo(pathname: *const c_char, mode: mode_t) -> c_int;
+
+        // read from or write to a file descriptor at a given offset
+        // doc: http://man7.org/linux/man-pages/man2/pread.2.html
+        pub fn pread(fd: c_int, buf: *mut c_void, count: size_t, offset: off_t) -> ssize_t;
+        pub fn pwrite(fd: c_int, buf: *const c_void, count: size_t, offset: off_t) -> ssize_t;
+
+        // create pipe with flags applied atomically
+        // doc: http://man7.org/linux/man-pages/man2/pipe.2.html
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        pub fn pipe2(pipefd: *mut c_int, flags: c_int) -> c_int;
+
+        // truncate a file to a specified length
+        // doc: http://man7.org/linux/man-pages/man2/truncate.2.html
+        pub fn truncate(path: *const c_char, length: off_t) -> c_int;
+
+        // synchronize a file's state with storage
+        // doc: http://man7.org/linux/man-pages/man2/fsync.2.html
+        pub fn fsync(fd: c_int) -> c_int;
+
+        // commit filesystem caches to disk
+        // doc: http://man7.org/linux/man-pages/man2/sync.2.html
+        pub fn sync();
 
         // execute program
         // doc: http://man7.org/linux/man-pages/man2/execve.2.html
@@ -45,9 +112,55 @@ mod ffi {
         // change root directory
         // doc: http://man7.org/linux/man-pages/man2/gethostname.2.html
         pub fn chroot(path: *const c_char) -> c_int;
+
+        // delete a name relative to a directory file descriptor
+        // doc: http://man7.org/linux/man-pages/man2/unlinkat.2.html
+        pub fn unlinkat(dirfd: c_int, pathname: *const c_char, flags: c_int) -> c_int;
+
+        // make a new name for a file relative to directory file descriptors
+        // doc: http://man7.org/linux/man-pages/man2/linkat.2.html
+        pub fn linkat(olddirfd: c_int, oldpath: *const c_char, newdirfd: c_int, newpath: *const c_char, flags: c_int) -> c_int;
+
+        // make a new symbolic link relative to a directory file descriptor
+        // doc: http://man7.org/linux/man-pages/man2/symlinkat.2.html
+        pub fn symlinkat(target: *const c_char, newdirfd: c_int, linkpath: *const c_char) -> c_int;
+
+        // read the value of a symbolic link relative to a directory file descriptor
+        // doc: http://man7.org/linux/man-pages/man2/readlinkat.2.html
+        pub fn readlinkat(dirfd: c_int, pathname: *const c_char, buf: *mut c_char, bufsiz: size_t) -> ssize_t;
+
+        // change ownership of a file
+        // doc: http://man7.org/linux/man-pages/man2/chown.2.html
+        pub fn chown(pathname: *const c_char, owner: uid_t, group: gid_t) -> c_int;
+        pub fn fchown(fd: c_int, owner: uid_t, group: gid_t) -> c_int;
+        pub fn lchown(pathname: *const c_char, owner: uid_t, group: gid_t) -> c_int;
+
+        // check user's permissions for a file
+        // doc: http://man7.org/linux/man-pages/man2/access.2.html
+        pub fn access(pathname: *const c_char, mode: c_int) -> c_int;
+
+        // change ownership of a file relative to a directory file descriptor
+        // doc: http://man7.org/linux/man-pages/man2/fchownat.2.html
+        pub fn fchownat(dirfd: c_int, pathname: *const c_char, owner: uid_t, group: gid_t, flags: c_int) -> c_int;
+
+        // check user's permissions for a file relative to a directory file descriptor
+        // doc: http://man7.org/linux/man-pages/man2/faccessat.2.html
+        pub fn faccessat(dirfd: c_int, pathname: *const c_char, mode: c_int, flags: c_int) -> c_int;
     }
 }
 
+pub type Uid = uid_t;
+pub type Gid = gid_t;
+
+bitflags!(
+    flags AccessFlags: c_int {
+        const F_OK = 0,
+        const X_OK = 1,
+        const W_OK = 2,
+        const R_OK = 4
+    }
+);
+
 #[derive(Clone, Copy)]
 pub enum Fork {
     Parent(pid_t),
@@ -106,6 +219,27 @@ pub fn dup2(oldfd: Fd, newfd: Fd) -> Result<Fd> {
     Ok(res)
 }
 
+/// Like `dup2`, with `O_CLOEXEC` from `flags` set atomically on the new
+/// descriptor where the kernel supports it. Fails with `EINVAL` if `oldfd`
+/// equals `newfd`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn dup3(oldfd: Fd, newfd: Fd, flags: OFlag) -> Result<Fd> {
+    if !features::dup3_available() {
+        return dup3_polyfill(oldfd, newfd, flags);
+    }
+
+    let res = unsafe { ffi::dup3(oldfd, newfd, flags.bits()) };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res)
+}
+
+/// Like `dup2`, with `O_CLOEXEC` from `flags` set on the new descriptor
+/// afterwards. Fails with `EINVAL` if `oldfd` equals `newfd`.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
 pub fn dup3(oldfd: Fd, newfd: Fd, flags: OFlag) -> Result<Fd> {
     dup3_polyfill(oldfd, newfd, flags)
 }
@@ -143,6 +277,40 @@ pub fn chdir<P: ?Sized + NixPath>(path: &P) -> Result<()> {
     return Ok(())
 }
 
+/// Change the working directory to the open directory `fd`
+pub fn fchdir(fd: Fd) -> Result<()> {
+    let res = unsafe { ffi::fchdir(fd) };
+    from_ffi(res)
+}
+
+/// Returns the current working directory, growing the buffer until the
+/// whole path fits.
+pub fn getcwd() -> Result<OsString> {
+    let mut buf: Vec<u8> = Vec::with_capacity(512);
+
+    loop {
+        let cap = buf.capacity();
+        let res = unsafe { ffi::getcwd(buf.as_mut_ptr() as *mut c_char, cap as size_t) };
+
+        if res.is_null() {
+            match Errno::last() {
+                Errno::ERANGE => {
+                    buf.reserve(cap * 2);
+                    continue;
+                }
+                errno => return Err(Error::Sys(errno)),
+            }
+        }
+
+        unsafe {
+            let len = ::libc::strlen(res) as usize;
+            buf.set_len(len);
+        }
+
+        return Ok(OsString::from_vec(buf));
+    }
+}
+
 #[inline]
 pub fn execve(filename: &CString, args: &[CString], env: &[CString]) -> Result<()> {
     let mut args_p: Vec<*const c_char> = args.iter().map(|s| s.as_ptr()).collect();
@@ -208,6 +376,60 @@ pub fn write(fd: Fd, buf: &[u8]) -> Result<usize> {
     return Ok(res as usize)
 }
 
+/// Like `read`, but reads from `offset` and leaves the file offset unchanged
+pub fn pread(fd: Fd, buf: &mut [u8], offset: off_t) -> Result<usize> {
+    let res = unsafe { ffi::pread(fd, buf.as_mut_ptr() as *mut c_void, buf.len() as size_t, offset) };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res as usize)
+}
+
+/// Like `write`, but writes at `offset` and leaves the file offset unchanged
+pub fn pwrite(fd: Fd, buf: &[u8], offset: off_t) -> Result<usize> {
+    let res = unsafe { ffi::pwrite(fd, buf.as_ptr() as *const c_void, buf.len() as size_t, offset) };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res as usize)
+}
+
+/// Where `lseek` measures the offset from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(i32)]
+pub enum Whence {
+    /// The start of the file
+    SeekSet = 0,
+    /// The current file offset
+    SeekCur = 1,
+    /// The end of the file
+    SeekEnd = 2,
+    /// The next data at or after the offset. Fails with `ENXIO` past the
+    /// end of the file.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    SeekData = 3,
+    /// The next hole at or after the offset. The end of the file counts as
+    /// a hole.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    SeekHole = 4,
+}
+
+/// Move the file offset of `fd` and return the new offset, measured from the
+/// start of the file.
+pub fn lseek(fd: Fd, offset: off_t, whence: Whence) -> Result<off_t> {
+    let res = unsafe { ffi::lseek(fd, offset, whence as c_int) };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(res)
+}
+
 pub fn pipe() -> Result<(Fd, Fd)> {
     unsafe {
         let mut res;
@@ -223,7 +445,44 @@ pub fn pipe() -> Result<(Fd, Fd)> {
     }
 }
 
+/// Create a pipe with `O_CLOEXEC` and `O_NONBLOCK` from `flags` set on both
+/// ends, atomically where the kernel supports it. With `O_DIRECT` (since
+/// Linux 3.4) the pipe works in packet mode: each write is a separate packet
+/// and each read returns at most one.
+///
+/// Kernels without `pipe2` only get `O_CLOEXEC` and `O_NONBLOCK`, and fail
+/// with `EINVAL` if `flags` holds anything else.
+#[cfg(any(target_os = "linux", target_os = "android"))]
 pub fn pipe2(flags: OFlag) -> Result<(Fd, Fd)> {
+    if !features::pipe2_available() {
+        return pipe2_polyfill(flags);
+    }
+
+    let mut fds: [c_int; 2] = [-1, -1];
+    let res = unsafe { ffi::pipe2(fds.as_mut_ptr(), flags.bits()) };
+
+    if res < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok((fds[0], fds[1]))
+}
+
+/// Create a pipe with `O_CLOEXEC` and `O_NONBLOCK` from `flags` set on both
+/// ends. The flags are set after the pipe is created, so a concurrent `fork`
+/// may inherit descriptors without `O_CLOEXEC`. Fails with `EINVAL` if
+/// `flags` holds any other flag.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub fn pipe2(flags: OFlag) -> Result<(Fd, Fd)> {
+    pipe2_polyfill(flags)
+}
+
+fn pipe2_polyfill(flags: OFlag) -> Result<(Fd, Fd)> {
+    // Anything else, such as O_DIRECT, cannot be set after the fact
+    if !(O_CLOEXEC | O_NONBLOCK).contains(flags) {
+        return Err(Error::invalid_argument());
+    }
+
     unsafe {
         let mut res;
         let mut fds: [c_int; 2] = mem::uninitialized();
@@ -241,7 +500,7 @@ pub fn pipe2(flags: OFlag) -> Result<(Fd, Fd)> {
 }
 
 fn pipe2_setflags(fd1: Fd, fd2: Fd, flags: OFlag) -> Result<()> {
-    let mut res = Ok(());
+    let mut res = Ok(0);
 
     if flags.contains(O_CLOEXEC) {
         res = res
@@ -265,6 +524,14 @@ fn pipe2_setflags(fd1: Fd, fd2: Fd, flags: OFlag) -> Result<()> {
     }
 }
 
+/// Truncate or extend the file at `path` to exactly `len` bytes
+pub fn truncate<P: ?Sized + NixPath>(path: &P, len: off_t) -> Result<()> {
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::truncate(osstr.as_ext_str(), len) }
+    }));
+    from_ffi(res)
+}
+
 pub fn ftruncate(fd: Fd, len: off_t) -> Result<()> {
     if unsafe { ffi::ftruncate(fd, len) } < 0 {
         Err(Error::Sys(Errno::last()))
@@ -273,6 +540,18 @@ pub fn ftruncate(fd: Fd, len: off_t) -> Result<()> {
     }
 }
 
+/// Flush the data and metadata of a file to the storage device, waiting
+/// until the device reports that they are written.
+pub fn fsync(fd: Fd) -> Result<()> {
+    let res = unsafe { ffi::fsync(fd) };
+    from_ffi(res)
+}
+
+/// Schedule all modified filesystem data and metadata to be written out.
+pub fn sync() {
+    unsafe { ffi::sync() }
+}
+
 pub fn isatty(fd: Fd) -> Result<bool> {
     use libc;
 
@@ -297,6 +576,265 @@ pub fn unlink<P: ?Sized + NixPath>(path: &P) -> Result<()> {
     from_ffi(res)
 }
 
+/// Create a directory with the permissions in `mode`, less the umask
+pub fn mkdir<P: ?Sized + NixPath>(path: &P, mode: Mode) -> Result<()> {
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::mkdir(osstr.as_ext_str(), mode.bits() as mode_t) }
+    }));
+    from_ffi(res)
+}
+
+/// Remove an empty directory
+pub fn rmdir<P: ?Sized + NixPath>(path: &P) -> Result<()> {
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::rmdir(osstr.as_ext_str()) }
+    }));
+    from_ffi(res)
+}
+
+/// Rename `old_path` to `new_path`, atomically replacing `new_path` if it
+/// exists
+pub fn rename<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(old_path: &P1, new_path: &P2) -> Result<()> {
+    let res = try!(try!(old_path.with_nix_path(|old| {
+        new_path.with_nix_path(|new| {
+            unsafe { ffi::rename(old.as_ext_str(), new.as_ext_str()) }
+        })
+    })));
+    from_ffi(res)
+}
+
+/// Create a hard link `new_path` to the file at `old_path`
+pub fn link<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(old_path: &P1, new_path: &P2) -> Result<()> {
+    let res = try!(try!(old_path.with_nix_path(|old| {
+        new_path.with_nix_path(|new| {
+            unsafe { ffi::link(old.as_ext_str(), new.as_ext_str()) }
+        })
+    })));
+    from_ffi(res)
+}
+
+/// Create a symbolic link `link_path` containing `target`, which need not
+/// exist
+pub fn symlink<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(target: &P1, link_path: &P2) -> Result<()> {
+    let res = try!(try!(target.with_nix_path(|target| {
+        link_path.with_nix_path(|link_path| {
+            unsafe { ffi::symlink(target.as_ext_str(), link_path.as_ext_str()) }
+        })
+    })));
+    from_ffi(res)
+}
+
+/// Returns the target of the symbolic link at `path`, growing the buffer
+/// until the whole target fits.
+pub fn readlink<P: ?Sized + NixPath>(path: &P) -> Result<OsString> {
+    try!(path.with_nix_path(|osstr| {
+        readlink_grow(|buf, len| unsafe { ffi::readlink(osstr.as_ext_str(), buf, len) })
+    }))
+}
+
+/// Create a FIFO with the permissions in `mode`, less the umask
+pub fn mkfifo<P: ?Sized + NixPath>(path: &P, mode: Mode) -> Result<()> {
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::mkfifo(osstr.as_ext_str(), mode.bits() as mode_t) }
+    }));
+    from_ffi(res)
+}
+
+/// Create and open a new file named after `template`, whose last six bytes
+/// must be `XXXXXX`. They are replaced to make the name unique. Returns the
+/// descriptor, open for reading and writing, and the path of the file,
+/// which was created with mode 0600.
+pub fn mkstemp<P: ?Sized + NixPath>(template: &P) -> Result<(Fd, PathBuf)> {
+    let mut buf = try!(temp_template(template, 0));
+    let fd = unsafe { ffi::mkstemp(buf.as_mut_ptr() as *mut c_char) };
+
+    if fd < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok((fd, temp_path(buf)))
+}
+
+/// Like `mkstemp`, with additional open flags such as `O_CLOEXEC` or
+/// `O_APPEND`
+pub fn mkostemp<P: ?Sized + NixPath>(template: &P, flags: OFlag) -> Result<(Fd, PathBuf)> {
+    let mut buf = try!(temp_template(template, 0));
+    let fd = unsafe { ffi::mkostemp(buf.as_mut_ptr() as *mut c_char, flags.bits()) };
+
+    if fd < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok((fd, temp_path(buf)))
+}
+
+/// Like `mkstemp`, but `template` ends with a `suffix_len` byte suffix after
+/// the `XXXXXX`, such as an extension.
+pub fn mkstemps<P: ?Sized + NixPath>(template: &P, suffix_len: usize) -> Result<(Fd, PathBuf)> {
+    let mut buf = try!(temp_template(template, suffix_len));
+    let fd = unsafe { ffi::mkstemps(buf.as_mut_ptr() as *mut c_char, suffix_len as c_int) };
+
+    if fd < 0 {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok((fd, temp_path(buf)))
+}
+
+/// Create a new directory named after `template`, like `mkstemp`. Returns
+/// its path; the directory was created with mode 0700.
+pub fn mkdtemp<P: ?Sized + NixPath>(template: &P) -> Result<PathBuf> {
+    let mut buf = try!(temp_template(template, 0));
+    let res = unsafe { ffi::mkdtemp(buf.as_mut_ptr() as *mut c_char) };
+
+    if res.is_null() {
+        return Err(Error::Sys(Errno::last()));
+    }
+
+    Ok(temp_path(buf))
+}
+
+// Copies `template` into a NUL-terminated buffer for the mkstemp family to
+// fill in. Not every libc insists on the `XXXXXX`, so it is checked here.
+fn temp_template<P: ?Sized + NixPath>(template: &P, suffix_len: usize) -> Result<Vec<u8>> {
+    let mut buf = try!(template.with_nix_path(|osstr| osstr.as_bytes().to_vec()));
+    let len = buf.len();
+
+    if len < suffix_len + 6 || &buf[len - suffix_len - 6..len - suffix_len] != b"XXXXXX" {
+        return Err(Error::invalid_argument());
+    }
+
+    buf.push(0);
+    Ok(buf)
+}
+
+fn temp_path(mut buf: Vec<u8>) -> PathBuf {
+    buf.pop();
+    PathBuf::from(OsString::from_vec(buf))
+}
+
+pub fn unlinkat<P: ?Sized + NixPath>(dirfd: DirFd, path: &P, flags: AtFlags) -> Result<()> {
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::unlinkat(dirfd.as_raw(), osstr.as_ext_str(), flags.bits()) }
+    }));
+    from_ffi(res)
+}
+
+pub fn linkat<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(
+        old_dirfd: DirFd, old_path: &P1, new_dirfd: DirFd, new_path: &P2, flags: AtFlags) -> Result<()> {
+    let res = try!(try!(old_path.with_nix_path(|old| {
+        new_path.with_nix_path(|new| {
+            unsafe {
+                ffi::linkat(old_dirfd.as_raw(), old.as_ext_str(),
+                            new_dirfd.as_raw(), new.as_ext_str(), flags.bits())
+            }
+        })
+    })));
+    from_ffi(res)
+}
+
+pub fn symlinkat<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(
+        target: &P1, dirfd: DirFd, link_path: &P2) -> Result<()> {
+    let res = try!(try!(target.with_nix_path(|target| {
+        link_path.with_nix_path(|link_path| {
+            unsafe { ffi::symlinkat(target.as_ext_str(), dirfd.as_raw(), link_path.as_ext_str()) }
+        })
+    })));
+    from_ffi(res)
+}
+
+/// Returns the target of the symbolic link at `path`, growing the buffer
+/// until the whole target fits.
+pub fn readlinkat<P: ?Sized + NixPath>(dirfd: DirFd, path: &P) -> Result<OsString> {
+    try!(path.with_nix_path(|osstr| {
+        readlink_grow(|buf, len| unsafe {
+            ffi::readlinkat(dirfd.as_raw(), osstr.as_ext_str(), buf, len)
+        })
+    }))
+}
+
+// readlink(2) silently truncates, so a result filling the whole buffer may
+// have been cut short and is retried with a larger one.
+fn readlink_grow<F>(f: F) -> Result<OsString>
+        where F: Fn(*mut c_char, size_t) -> ssize_t {
+    let mut buf: Vec<u8> = Vec::with_capacity(256);
+
+    loop {
+        let cap = buf.capacity();
+        let res = f(buf.as_mut_ptr() as *mut c_char, cap as size_t);
+
+        if res < 0 {
+            return Err(Error::Sys(Errno::last()));
+        }
+
+        if (res as usize) < cap {
+            unsafe { buf.set_len(res as usize) };
+            return Ok(OsString::from_vec(buf));
+        }
+
+        buf.reserve(cap * 2);
+    }
+}
+
+/// Change the owner and group of the file at `path`, following symbolic
+/// links. A `None` leaves that side unchanged.
+pub fn chown<P: ?Sized + NixPath>(path: &P, owner: Option<Uid>, group: Option<Gid>) -> Result<()> {
+    let (uid, gid) = chown_ids(owner, group);
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::chown(osstr.as_ext_str(), uid, gid) }
+    }));
+    from_ffi(res)
+}
+
+/// Like `chown`, but for an open file
+pub fn fchown(fd: Fd, owner: Option<Uid>, group: Option<Gid>) -> Result<()> {
+    let (uid, gid) = chown_ids(owner, group);
+    let res = unsafe { ffi::fchown(fd, uid, gid) };
+    from_ffi(res)
+}
+
+/// Like `chown`, but changes a symbolic link at `path` itself
+pub fn lchown<P: ?Sized + NixPath>(path: &P, owner: Option<Uid>, group: Option<Gid>) -> Result<()> {
+    let (uid, gid) = chown_ids(owner, group);
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::lchown(osstr.as_ext_str(), uid, gid) }
+    }));
+    from_ffi(res)
+}
+
+/// Change the owner and group of a file. A `None` leaves that side unchanged.
+pub fn fchownat<P: ?Sized + NixPath>(dirfd: DirFd, path: &P, owner: Option<Uid>, group: Option<Gid>, flags: AtFlags) -> Result<()> {
+    let (uid, gid) = chown_ids(owner, group);
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::fchownat(dirfd.as_raw(), osstr.as_ext_str(), uid, gid, flags.bits()) }
+    }));
+    from_ffi(res)
+}
+
+// The chown family uses -1 for an id that should be left unchanged
+fn chown_ids(owner: Option<Uid>, group: Option<Gid>) -> (uid_t, gid_t) {
+    (owner.unwrap_or(!0), group.unwrap_or(!0))
+}
+
+/// Check whether the calling process may access the file at `path` in the
+/// ways given by `mode`, using its real user and group IDs. `F_OK` only
+/// checks that the file exists.
+pub fn access<P: ?Sized + NixPath>(path: &P, mode: AccessFlags) -> Result<()> {
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::access(osstr.as_ext_str(), mode.bits()) }
+    }));
+    from_ffi(res)
+}
+
+/// Like `access`, relative to `dirfd`. With `AT_EACCESS` the effective user
+/// and group IDs are used instead.
+pub fn faccessat<P: ?Sized + NixPath>(dirfd: DirFd, path: &P, mode: AccessFlags, flags: AtFlags) -> Result<()> {
+    let res = try!(path.with_nix_path(|osstr| {
+        unsafe { ffi::faccessat(dirfd.as_raw(), osstr.as_ext_str(), mode.bits(), flags.bits()) }
+    }));
+    from_ffi(res)
+}
+
 #[inline]
 pub fn chroot<P: ?Sized + NixPath>(path: &P) -> Result<()> {
     let res = try!(path.with_nix_path(|osstr| {
@@ -314,7 +852,61 @@ pub fn chroot<P: ?Sized + NixPath>(path: &P) -> Result<()> {
 mod linux {
     use sys::syscall::{syscall, SYSPIVOTROOT};
     use errno::Errno;
-    use {Error, Result, NixPath};
+    use fcntl::Fd;
+    use libc::c_int;
+    use fcntl::fcntl;
+    use fcntl::FcntlArg::{F_GETPIPE_SZ, F_SETPIPE_SZ};
+    use super::Whence;
+    use {Error, Result, NixPath, from_ffi};
+
+    mod ffi {
+        use libc::c_int;
+
+        extern {
+            pub fn fdatasync(fd: c_int) -> c_int;
+            pub fn syncfs(fd: c_int) -> c_int;
+            pub fn lseek64(fd: c_int, offset: i64, whence: c_int) -> i64;
+        }
+    }
+
+    /// Returns the capacity in bytes of the pipe `fd` is an end of
+    pub fn pipe_size(fd: Fd) -> Result<usize> {
+        fcntl(fd, F_GETPIPE_SZ).map(|size| size as usize)
+    }
+
+    /// Set the capacity of the pipe `fd` is an end of to at least `size`
+    /// bytes, and return the capacity actually set. Without
+    /// `CAP_SYS_RESOURCE`, it fails with `EPERM` above
+    /// `/proc/sys/fs/pipe-max-size`. It fails with `EBUSY` below the amount
+    /// of data currently in the pipe.
+    pub fn set_pipe_size(fd: Fd, size: usize) -> Result<usize> {
+        fcntl(fd, F_SETPIPE_SZ(size as c_int)).map(|size| size as usize)
+    }
+
+    /// Like `lseek`, but with a 64-bit offset even where `off_t` is 32 bits
+    pub fn lseek64(fd: Fd, offset: i64, whence: Whence) -> Result<i64> {
+        let res = unsafe { ffi::lseek64(fd, offset, whence as c_int) };
+
+        if res < 0 {
+            return Err(Error::Sys(Errno::last()));
+        }
+
+        Ok(res)
+    }
+
+    /// Like `fsync`, but skips metadata that is not needed to read the data
+    /// back, such as the modification time.
+    pub fn fdatasync(fd: Fd) -> Result<()> {
+        let res = unsafe { ffi::fdatasync(fd) };
+        from_ffi(res)
+    }
+
+    /// Like `sync`, but only for the filesystem containing `fd`, and reports
+    /// writeback errors since the descriptor was opened.
+    pub fn syncfs(fd: Fd) -> Result<()> {
+        let res = unsafe { ffi::syncfs(fd) };
+        from_ffi(res)
+    }
 
     pub fn pivot_root<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(
             new_root: &P1, put_old: &P2) -> Result<()> {
@@ -1,3 +1,24 @@
 mod test_socket;
 mod test_termios;
 mod test_uio;
+
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos", target_os = "ios"))]
+mod test_statvfs;
+
+#[cfg(target_os = "linux")]
+mod test_landlock;
+
+#[cfg(target_os = "linux")]
+mod test_xattr;
+
+#[cfg(target_os = "linux")]
+mod test_inotify;
+
+#[cfg(target_os = "linux")]
+mod test_fanotify;
+
+#[cfg(target_os = "linux")]
+mod test_sendfile;
+
+#[cfg(target_os = "linux")]
+mod test_memfd;
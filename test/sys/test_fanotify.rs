@@ -0,0 +1,106 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::fcntl::{open, DirFd, O_CREAT, O_RDONLY, O_WRONLY, O_CLOEXEC};
+use nix::sys::fanotify::*;
+use nix::sys::stat::S_IRWXU;
+use nix::unistd::close;
+use std::thread;
+use tempdir::TempDir;
+
+// fanotify needs CAP_SYS_ADMIN, which the tests may not have
+fn init(flags: InitFlags) -> Option<Fanotify> {
+    match Fanotify::init(flags, O_RDONLY | O_CLOEXEC) {
+        Ok(group) => Some(group),
+        Err(e @ Error::Sys(Errno::EPERM)) | Err(e @ Error::Sys(Errno::ENOSYS)) => {
+            println!("skipping: fanotify is unavailable ({:?})", e);
+            None
+        }
+        Err(e) => panic!("fanotify_init: {:?}", e),
+    }
+}
+
+#[test]
+fn test_fanotify_permission() {
+    let group = match init(FAN_CLOEXEC | FAN_CLASS_CONTENT) {
+        Some(group) => group,
+        None => return,
+    };
+
+    let dir = TempDir::new("fanotify-perm");
+    let file = dir.join("file");
+    let fd = open(file.as_bytes(), O_CREAT | O_WRONLY, S_IRWXU).unwrap();
+    close(fd).unwrap();
+
+    group.mark(FAN_MARK_ADD, FAN_OPEN_PERM, DirFd::Cwd, file.as_bytes()).unwrap();
+
+    let mut buf = [0u8; 4096];
+
+    for &response in [FAN_ALLOW, FAN_DENY].iter() {
+        let path = file.clone();
+        let opener = thread::spawn(move || {
+            open(path.as_bytes(), O_RDONLY, S_IRWXU).map(|fd| close(fd).unwrap())
+        });
+
+        let events: Vec<_> = group.read_events(&mut buf).unwrap().collect();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].mask == FAN_OPEN_PERM);
+        assert_eq!(events[0].pid, unsafe { ::libc::getpid() });
+        assert_eq!(events[0].info().count(), 0);
+
+        let event_fd = events[0].fd.unwrap();
+        group.write_response(event_fd, response).unwrap();
+        close(event_fd).unwrap();
+
+        let res = opener.join().unwrap();
+
+        if response == FAN_ALLOW {
+            assert_eq!(res, Ok(()));
+        } else {
+            assert_eq!(res, Err(Error::Sys(Errno::EPERM)));
+        }
+    }
+
+    group.mark(FAN_MARK_REMOVE, FAN_OPEN_PERM, DirFd::Cwd, file.as_bytes()).unwrap();
+}
+
+#[test]
+fn test_fanotify_fid() {
+    let group = match init(FAN_CLOEXEC | FAN_NONBLOCK | FAN_CLASS_NOTIF | FAN_REPORT_DFID_NAME) {
+        Some(group) => group,
+        None => return,
+    };
+
+    let dir = TempDir::new("fanotify-fid");
+    let file = dir.join("file");
+
+    match group.mark(FAN_MARK_ADD, FAN_CREATE | FAN_EVENT_ON_CHILD, DirFd::Cwd, dir.path().as_bytes()) {
+        Err(Error::Sys(Errno::EOPNOTSUPP)) | Err(Error::Sys(Errno::EXDEV)) => {
+            println!("skipping: the filesystem cannot encode file handles");
+            return;
+        }
+        res => res.unwrap(),
+    }
+
+    let mut buf = [0u8; 4096];
+    assert_eq!(group.read_events(&mut buf).err(), Some(Error::Sys(Errno::EAGAIN)));
+
+    let fd = open(file.as_bytes(), O_CREAT | O_WRONLY, S_IRWXU).unwrap();
+    close(fd).unwrap();
+
+    let events: Vec<_> = group.read_events(&mut buf).unwrap().collect();
+    assert_eq!(events.len(), 1);
+    assert!(events[0].mask == FAN_CREATE);
+    assert_eq!(events[0].fd, None);
+
+    let info: Vec<_> = events[0].info().collect();
+    assert_eq!(info.len(), 1);
+
+    match info[0] {
+        InfoRecord::Fid(fid) => {
+            assert_eq!(fid.info_type, FAN_EVENT_INFO_TYPE_DFID_NAME);
+            assert!(fid.handle.len() > 0);
+            assert_eq!(fid.name, Some(b"file".as_ref()));
+        }
+        _ => panic!("expected a file handle record"),
+    }
+}
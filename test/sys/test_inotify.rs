@@ -0,0 +1,48 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::fcntl::{open, DirFd, AtFlags, O_CREAT, O_WRONLY};
+use nix::sys::epoll::*;
+use nix::sys::inotify::*;
+use nix::sys::stat::S_IRWXU;
+use nix::unistd::{close, unlinkat};
+use tempdir::TempDir;
+
+#[test]
+fn test_inotify() {
+    let dir = TempDir::new("inotify");
+    let file = dir.join("file");
+
+    let inotify = Inotify::init(IN_CLOEXEC | IN_NONBLOCK).unwrap();
+    let wd = inotify.add_watch(dir.path().as_bytes(), IN_CREATE | IN_DELETE).unwrap();
+    let mut buf = [0u8; 4096];
+
+    assert_eq!(inotify.read_events(&mut buf).err(), Some(Error::Sys(Errno::EAGAIN)));
+
+    let epfd = epoll_create().unwrap();
+    let event = EpollEvent { events: EPOLLIN, data: 7 };
+    epoll_ctl(epfd, EpollOp::EpollCtlAdd, inotify.as_raw_fd(), &event).unwrap();
+
+    let fd = open(file.as_bytes(), O_CREAT | O_WRONLY, S_IRWXU).unwrap();
+    close(fd).unwrap();
+    unlinkat(DirFd::Cwd, file.as_bytes(), AtFlags::empty()).unwrap();
+
+    let mut ready = [EpollEvent { events: EpollEventKind::empty(), data: 0 }];
+    assert_eq!(epoll_wait(epfd, &mut ready, 1000).unwrap(), 1);
+    let data = ready[0].data;
+    assert_eq!(data, 7);
+    close(epfd).unwrap();
+
+    let events: Vec<_> = inotify.read_events(&mut buf).unwrap().collect();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].wd, wd);
+    assert!(events[0].mask == IN_CREATE);
+    assert_eq!(events[0].name, Some(b"file".as_ref()));
+    assert!(events[1].mask == IN_DELETE);
+    assert_eq!(events[1].name, Some(b"file".as_ref()));
+
+    inotify.rm_watch(wd).unwrap();
+    let events: Vec<_> = inotify.read_events(&mut buf).unwrap().collect();
+    assert_eq!(events.len(), 1);
+    assert!(events[0].mask == IN_IGNORED);
+    assert_eq!(events[0].name, None);
+}
@@ -0,0 +1,68 @@
+use nix::sys::landlock::*;
+use nix::errno::Errno;
+use nix::{Error, Result};
+use nix::fcntl::{open, O_PATH, O_DIRECTORY, O_RDONLY};
+use nix::sys::stat::Mode;
+use nix::unistd::{close, fork, pipe, read, write};
+use nix::unistd::Fork::{Child, Parent};
+use nix::sys::wait::waitpid;
+use libc;
+
+#[test]
+fn test_access_fs_from_abi() {
+    assert!(AccessFs::from_abi(0).is_empty());
+    assert!(!AccessFs::from_abi(1).contains(LANDLOCK_ACCESS_FS_REFER));
+    assert!(AccessFs::from_abi(2).contains(LANDLOCK_ACCESS_FS_REFER));
+    assert!(!AccessFs::from_abi(2).contains(LANDLOCK_ACCESS_FS_TRUNCATE));
+    assert!(AccessFs::from_abi(3).contains(LANDLOCK_ACCESS_FS_TRUNCATE));
+    assert!(AccessFs::from_abi(5).is_all());
+}
+
+#[test]
+fn test_landlock_restrict_self() {
+    if let Err(e) = landlock_abi_version() {
+        println!("skipping: Landlock is unavailable ({:?})", e);
+        return;
+    }
+
+    // Only allow reading files beneath /dev
+    fn restricted_opens() -> Result<[u8; 2]> {
+        let handled = LANDLOCK_ACCESS_FS_READ_FILE | LANDLOCK_ACCESS_FS_READ_DIR;
+        let (ruleset, handled) = match try!(landlock_create_ruleset_best_effort(handled)) {
+            Some(ruleset) => ruleset,
+            None => return Err(Error::Sys(Errno::ENOSYS)),
+        };
+        let dev = try!(open(b"/dev".as_ref(), O_PATH | O_DIRECTORY, Mode::empty()));
+        try!(landlock_add_rule(ruleset, dev, handled));
+        try!(close(dev));
+        try!(set_no_new_privs());
+        try!(landlock_restrict_self(ruleset));
+        try!(close(ruleset));
+
+        let allowed = open(b"/dev/null".as_ref(), O_RDONLY, Mode::empty()).is_ok();
+        let denied = open(b"/etc/passwd".as_ref(), O_RDONLY, Mode::empty()) ==
+            Err(Error::Sys(Errno::EACCES));
+
+        Ok([allowed as u8, denied as u8])
+    }
+
+    let (reader, writer) = pipe().unwrap();
+
+    match fork().unwrap() {
+        // A failure must not unwind into the test harness of the child
+        Child => {
+            let res = restricted_opens().and_then(|buf| write(writer, &buf));
+            unsafe { libc::_exit(if res.is_ok() { 0 } else { 1 }) };
+        }
+        Parent(child_pid) => {
+            // Otherwise the read below never sees end of file
+            close(writer).unwrap();
+            waitpid(child_pid, None).unwrap();
+
+            let mut buf = [0u8; 2];
+            assert_eq!(read(reader, &mut buf).unwrap(), 2);
+            assert_eq!(buf, [1, 1]);
+            close(reader).unwrap();
+        }
+    }
+}
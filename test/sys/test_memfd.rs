@@ -0,0 +1,42 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::fcntl::{fcntl, FcntlArg, SealFlag, F_SEAL_GROW, F_SEAL_SEAL, F_SEAL_SHRINK, F_SEAL_WRITE};
+use nix::sys::memfd::*;
+use nix::unistd::{close, ftruncate, pread, write, readlink};
+
+#[test]
+fn test_memfd_seals() {
+    let fd = memfd_create(b"nix-test".as_ref(), MFD_CLOEXEC | MFD_ALLOW_SEALING).unwrap();
+
+    let link = readlink(format!("/proc/self/fd/{}", fd).as_bytes()).unwrap();
+    assert_eq!(link, "/memfd:nix-test (deleted)");
+
+    write(fd, b"immutable").unwrap();
+    assert_eq!(fcntl(fd, FcntlArg::F_GET_SEALS).unwrap(), 0);
+
+    fcntl(fd, FcntlArg::F_ADD_SEALS(F_SEAL_SHRINK | F_SEAL_GROW | F_SEAL_WRITE)).unwrap();
+    fcntl(fd, FcntlArg::F_ADD_SEALS(F_SEAL_SEAL)).unwrap();
+    let seals = SealFlag::from_bits_truncate(fcntl(fd, FcntlArg::F_GET_SEALS).unwrap());
+    assert!(seals == F_SEAL_SHRINK | F_SEAL_GROW | F_SEAL_WRITE | F_SEAL_SEAL);
+
+    assert_eq!(write(fd, b"x"), Err(Error::Sys(Errno::EPERM)));
+    assert_eq!(ftruncate(fd, 4), Err(Error::Sys(Errno::EPERM)));
+    assert_eq!(ftruncate(fd, 100), Err(Error::Sys(Errno::EPERM)));
+    assert_eq!(fcntl(fd, FcntlArg::F_ADD_SEALS(F_SEAL_SHRINK)), Err(Error::Sys(Errno::EPERM)));
+
+    let mut buf = [0u8; 16];
+    assert_eq!(pread(fd, &mut buf, 0).unwrap(), 9);
+    assert_eq!(&buf[..9], b"immutable");
+
+    close(fd).unwrap();
+}
+
+#[test]
+fn test_memfd_without_sealing() {
+    // Files not created with MFD_ALLOW_SEALING start out sealed
+    let fd = memfd_create(b"nix-test".as_ref(), MemFdCreateFlag::empty()).unwrap();
+    let seals = SealFlag::from_bits_truncate(fcntl(fd, FcntlArg::F_GET_SEALS).unwrap());
+    assert!(seals == F_SEAL_SEAL);
+    assert_eq!(fcntl(fd, FcntlArg::F_ADD_SEALS(F_SEAL_WRITE)), Err(Error::Sys(Errno::EPERM)));
+    close(fd).unwrap();
+}
@@ -0,0 +1,34 @@
+use nix::fcntl::{open, O_CREAT, O_RDONLY, O_RDWR};
+use nix::sys::sendfile::sendfile;
+use nix::sys::stat::{S_IRUSR, S_IWUSR};
+use nix::unistd::{close, pipe, read, write};
+use tempdir::TempDir;
+
+#[test]
+fn test_sendfile() {
+    let dir = TempDir::new("sendfile");
+    let file = dir.join("file");
+    let fd = open(file.as_bytes(), O_CREAT | O_RDWR, S_IRUSR | S_IWUSR).unwrap();
+    write(fd, b"0123456789").unwrap();
+    close(fd).unwrap();
+
+    let fd = open(file.as_bytes(), O_RDONLY, S_IRUSR).unwrap();
+    let (rd, wr) = pipe().unwrap();
+    let mut buf = [0u8; 16];
+
+    let mut off = 5;
+    assert_eq!(sendfile(wr, fd, Some(&mut off), 3).unwrap(), 3);
+    assert_eq!(off, 8);
+    assert_eq!(read(rd, &mut buf).unwrap(), 3);
+    assert_eq!(&buf[..3], b"567");
+
+    // Without an offset, the file offset is used and advanced
+    assert_eq!(sendfile(wr, fd, None, 4).unwrap(), 4);
+    assert_eq!(sendfile(wr, fd, None, 2).unwrap(), 2);
+    assert_eq!(read(rd, &mut buf).unwrap(), 6);
+    assert_eq!(&buf[..6], b"012345");
+
+    close(rd).unwrap();
+    close(wr).unwrap();
+    close(fd).unwrap();
+}
@@ -4,6 +4,7 @@ use std::path::Path;
 use std::str::FromStr;
 use std::os::unix::io::AsRawFd;
 use ports::localhost;
+use tempdir::TempDir;
 
 #[test]
 pub fn test_inetv4_addr_to_sock_addr() {
@@ -63,3 +64,64 @@ pub fn test_socketpair() {
 
     assert_eq!(&buf[..], b"hello");
 }
+
+#[test]
+pub fn test_sockaddr_storage_unsupported_family() {
+    use nix::Error;
+    use nix::errno::Errno;
+    use nix::sys::socket::{sockaddr_storage, sockaddr_storage_to_addr};
+
+    // A zeroed storage holds AF_UNSPEC
+    let addr: sockaddr_storage = unsafe { mem::zeroed() };
+    let res = unsafe { sockaddr_storage_to_addr(&addr, mem::size_of::<sockaddr_storage>()) };
+    assert_eq!(res.err(), Some(Error::Sys(Errno::EAFNOSUPPORT)));
+}
+
+#[test]
+pub fn test_accept_with_addr() {
+    use nix::sys::socket::accept_with_addr;
+    use nix::unistd::close;
+    use std::net::{TcpListener, TcpStream};
+
+    let addr = localhost();
+    let listener = TcpListener::bind(&*addr).unwrap();
+    let stream = TcpStream::connect(&*addr).unwrap();
+
+    let (fd, peer) = accept_with_addr(listener.as_raw_fd()).unwrap();
+    assert_eq!(peer.to_str(), stream.local_addr().unwrap().to_string());
+
+    close(fd).unwrap();
+}
+
+#[test]
+pub fn test_accept4_with_addr() {
+    use nix::fcntl::{fcntl, FcntlArg, FD_CLOEXEC, O_NONBLOCK};
+    use nix::sys::socket::{accept4_with_addr, bind, connect, listen, socket, AddressFamily,
+                           SockAddr, SockType, SockFlag, SOCK_CLOEXEC, SOCK_NONBLOCK};
+    use nix::unistd::close;
+
+    let dir = TempDir::new("accept4");
+    let path = dir.join("socket");
+    let addr = SockAddr::new_unix(path.as_bytes()).unwrap();
+
+    let listener = socket(AddressFamily::Unix, SockType::Stream, SockFlag::empty()).unwrap();
+    bind(listener, &addr).unwrap();
+    listen(listener, 1).unwrap();
+
+    let client = socket(AddressFamily::Unix, SockType::Stream, SockFlag::empty()).unwrap();
+    connect(client, &addr).unwrap();
+
+    let (fd, peer) = accept4_with_addr(listener, SOCK_CLOEXEC | SOCK_NONBLOCK).unwrap();
+    assert_eq!(fcntl(fd, FcntlArg::F_GETFD).unwrap(), FD_CLOEXEC.bits());
+    assert!(fcntl(fd, FcntlArg::F_GETFL).unwrap() & O_NONBLOCK.bits() != 0);
+
+    // The client never bound a name
+    match peer {
+        SockAddr::Unix(peer) => assert_eq!(peer.path(), Path::new("")),
+        _ => panic!("expected a unix address"),
+    }
+
+    close(fd).unwrap();
+    close(client).unwrap();
+    close(listener).unwrap();
+}
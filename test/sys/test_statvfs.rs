@@ -0,0 +1,44 @@
+use nix::sys::statvfs::*;
+use nix::fcntl::{open, O_RDONLY};
+use nix::sys::stat::Mode;
+use nix::unistd::close;
+
+#[test]
+fn test_statvfs_fstatvfs() {
+    let vfs = statvfs(b"/".as_ref()).unwrap();
+
+    assert!(vfs.fragment_size() > 0);
+    assert!(vfs.total_bytes() >= vfs.free_bytes());
+    assert!(vfs.free_bytes() >= vfs.available_bytes());
+
+    let fd = open(b"/".as_ref(), O_RDONLY, Mode::empty()).unwrap();
+    let fvfs = fstatvfs(fd).unwrap();
+    close(fd).unwrap();
+
+    assert_eq!(vfs.filesystem_id(), fvfs.filesystem_id());
+    assert_eq!(vfs.blocks(), fvfs.blocks());
+}
+
+#[test]
+fn test_statvfs_enoent() {
+    assert!(statvfs(b"/nonexistent/nix-test".as_ref()).is_err());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_statfs() {
+    let proc_fs = statfs(b"/proc".as_ref()).unwrap();
+    assert_eq!(proc_fs.filesystem_type(), PROC_SUPER_MAGIC);
+
+    let fd = open(b"/proc".as_ref(), O_RDONLY, Mode::empty()).unwrap();
+    let fproc_fs = fstatfs(fd).unwrap();
+    close(fd).unwrap();
+
+    assert_eq!(fproc_fs.filesystem_type(), PROC_SUPER_MAGIC);
+
+    // Both count blocks in fragments
+    let vfs = statvfs(b"/".as_ref()).unwrap();
+    let fs = statfs(b"/".as_ref()).unwrap();
+    assert_eq!(fs.fragment_size(), vfs.fragment_size());
+    assert_eq!(fs.total_bytes(), vfs.total_bytes());
+}
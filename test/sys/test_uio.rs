@@ -2,6 +2,7 @@ use nix::sys::uio::*;
 use nix::unistd::*;
 use rand::{thread_rng, Rng};
 use std::{cmp, iter};
+use tempdir::TempDir;
 
 #[test]
 fn test_writev() {
@@ -90,3 +91,59 @@ fn test_readv() {
     let close_res = close(writer);
     assert!(close_res.is_ok());
 }
+
+#[test]
+fn test_pwritev_preadv() {
+    use nix::fcntl::{open, O_CREAT, O_RDWR};
+    use nix::sys::stat::{S_IRUSR, S_IWUSR};
+
+    let dir = TempDir::new("preadv");
+    let path = dir.join("file");
+    let fd = open(path.as_bytes(), O_CREAT | O_RDWR, S_IRUSR | S_IWUSR).unwrap();
+
+    let iov = [IoVec::from_slice(b"0123"), IoVec::from_slice(b"4567")];
+    assert_eq!(pwritev(fd, &iov, 2).unwrap(), 8);
+
+    let mut a = [0u8; 3];
+    let mut b = [0u8; 4];
+    {
+        let mut iov = [IoVec::from_mut_slice(&mut a), IoVec::from_mut_slice(&mut b)];
+        assert_eq!(preadv(fd, &mut iov, 3).unwrap(), 7);
+    }
+    assert_eq!(&a, b"123");
+    assert_eq!(&b, b"4567");
+
+    // Neither moves the file offset
+    assert_eq!(lseek(fd, 0, Whence::SeekCur).unwrap(), 0);
+
+    close(fd).unwrap();
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_pwritev2_preadv2() {
+    use nix::fcntl::{open, O_CREAT, O_RDWR};
+    use nix::sys::stat::{S_IRUSR, S_IWUSR};
+
+    let dir = TempDir::new("preadv2");
+    let path = dir.join("file");
+    let fd = open(path.as_bytes(), O_CREAT | O_RDWR, S_IRUSR | S_IWUSR).unwrap();
+
+    let iov = [IoVec::from_slice(b"abc")];
+    assert_eq!(pwritev2(fd, &iov, 0, RWF_DSYNC).unwrap(), 3);
+
+    // An offset of -1 uses the file offset, which RWF_APPEND ignores
+    assert_eq!(pwritev2(fd, &iov, -1, RWF_APPEND).unwrap(), 3);
+    assert_eq!(lseek(fd, 0, Whence::SeekCur).unwrap(), 6);
+    lseek(fd, 1, Whence::SeekSet).unwrap();
+
+    let mut buf = [0u8; 8];
+    {
+        let mut iov = [IoVec::from_mut_slice(&mut buf)];
+        assert_eq!(preadv2(fd, &mut iov, -1, ReadWriteFlags::empty()).unwrap(), 5);
+    }
+    assert_eq!(&buf[..5], b"bcabc");
+    assert_eq!(lseek(fd, 0, Whence::SeekCur).unwrap(), 6);
+
+    close(fd).unwrap();
+}
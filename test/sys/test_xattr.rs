@@ -0,0 +1,59 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::fcntl::{open, DirFd, O_CREAT, O_RDWR};
+use nix::sys::stat::{S_IRUSR, S_IWUSR};
+use nix::sys::xattr::*;
+use nix::unistd::{close, symlinkat};
+use std::ffi::OsString;
+use tempdir::TempDir;
+
+#[test]
+fn test_xattr() {
+    let dir = TempDir::new("xattr");
+    let file = dir.join("file");
+    let fd = open(file.as_bytes(), O_CREAT | O_RDWR, S_IRUSR | S_IWUSR).unwrap();
+
+    match setxattr(file.as_bytes(), b"user.nix.a".as_ref(), b"hello", XattrFlags::empty()) {
+        Err(Error::Sys(Errno::EOPNOTSUPP)) => {
+            println!("skipping: the filesystem does not support user attributes");
+            close(fd).unwrap();
+            return;
+        }
+        res => res.unwrap(),
+    }
+
+    assert_eq!(getxattr(file.as_bytes(), b"user.nix.a".as_ref()).unwrap(), b"hello");
+    assert_eq!(fgetxattr(fd, b"user.nix.a".as_ref()).unwrap(), b"hello");
+
+    let big = vec![7u8; 1000];
+    fsetxattr(fd, b"user.nix.b".as_ref(), &big, XATTR_CREATE).unwrap();
+    assert_eq!(fgetxattr(fd, b"user.nix.b".as_ref()).unwrap(), big);
+
+    assert_eq!(fsetxattr(fd, b"user.nix.b".as_ref(), b"", XATTR_CREATE),
+               Err(Error::Sys(Errno::EEXIST)));
+    assert_eq!(setxattr(file.as_bytes(), b"user.nix.c".as_ref(), b"", XATTR_REPLACE),
+               Err(Error::Sys(Errno::ENODATA)));
+
+    let mut names: Vec<OsString> = listxattr(file.as_bytes()).unwrap()
+        .filter(|n| n.to_str().map_or(false, |n| n.starts_with("user.nix.")))
+        .collect();
+    names.sort();
+    assert_eq!(names, vec![OsString::from("user.nix.a"), OsString::from("user.nix.b")]);
+    assert_eq!(flistxattr(fd).unwrap().count(), listxattr(file.as_bytes()).unwrap().count());
+
+    // The l* variants act on the link, which cannot carry user attributes
+    let link = dir.join("link");
+    symlinkat(file.as_bytes(), DirFd::Cwd, link.as_bytes()).unwrap();
+    assert_eq!(getxattr(link.as_bytes(), b"user.nix.a".as_ref()).unwrap(), b"hello");
+    assert_eq!(lgetxattr(link.as_bytes(), b"user.nix.a".as_ref()),
+               Err(Error::Sys(Errno::ENODATA)));
+    assert_eq!(llistxattr(link.as_bytes()).unwrap()
+                   .filter(|n| n.to_str().map_or(false, |n| n.starts_with("user."))).count(), 0);
+
+    removexattr(file.as_bytes(), b"user.nix.a".as_ref()).unwrap();
+    fremovexattr(fd, b"user.nix.b".as_ref()).unwrap();
+    assert_eq!(getxattr(file.as_bytes(), b"user.nix.a".as_ref()),
+               Err(Error::Sys(Errno::ENODATA)));
+
+    close(fd).unwrap();
+}
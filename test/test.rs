@@ -3,6 +3,8 @@ extern crate libc;
 extern crate rand;
 
 mod sys;
+mod test_dir;
+mod test_fcntl;
 mod test_nix_path;
 mod test_stat;
 mod test_unistd;
@@ -33,3 +35,36 @@ mod ports {
         format!("127.0.0.1:{}", next_port())
     }
 }
+
+mod tempdir {
+    use std::fs;
+
+    // A directory under /tmp for a single test, removed along with
+    // everything in it when dropped
+    pub struct TempDir {
+        path: String,
+    }
+
+    impl TempDir {
+        pub fn new(name: &str) -> TempDir {
+            let path = format!("/tmp/nix-test-{}-{}", name, unsafe { ::libc::getpid() });
+            fs::create_dir(&path).unwrap();
+            TempDir { path: path }
+        }
+
+        pub fn path(&self) -> &str {
+            &self.path
+        }
+
+        // The path of `name` inside the directory
+        pub fn join(&self, name: &str) -> String {
+            format!("{}/{}", self.path, name)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+}
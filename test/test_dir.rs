@@ -0,0 +1,152 @@
+use nix::dir::Dir;
+use nix::fcntl::{openat, DirFd, O_CREAT, O_RDONLY, O_WRONLY, O_CLOEXEC};
+use nix::sys::stat::{mkdirat, fstat, FileType, S_IRWXU, S_IRUSR};
+use nix::unistd::close;
+use tempdir::TempDir;
+
+fn names(dir: &mut Dir) -> Vec<Vec<u8>> {
+    let mut names: Vec<Vec<u8>> = dir.iter()
+        .map(|e| e.unwrap().file_name().to_vec())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_dir_iter() {
+    let tmp = TempDir::new("dir");
+
+    let mut dir = Dir::open(tmp.path().as_bytes(), O_RDONLY | O_CLOEXEC, S_IRUSR).unwrap();
+    let dirfd = DirFd::Fd(dir.as_raw_fd());
+
+    let fd = openat(dirfd, b"file".as_ref(), O_CREAT | O_WRONLY, S_IRUSR).unwrap();
+    let ino = fstat(fd).unwrap().st_ino as u64;
+    close(fd).unwrap();
+    mkdirat(dirfd, b"subdir".as_ref(), S_IRWXU).unwrap();
+
+    dir.rewind();
+    let start = dir.tell().unwrap();
+
+    let expected: Vec<Vec<u8>> = vec![b".".to_vec(), b"..".to_vec(), b"file".to_vec(), b"subdir".to_vec()];
+    assert_eq!(names(&mut dir), expected);
+
+    // Iterating again yields nothing until the stream is rewound
+    assert_eq!(dir.iter().count(), 0);
+    dir.seek(start);
+    assert_eq!(names(&mut dir), expected);
+
+    dir.rewind();
+    for entry in dir.iter() {
+        let entry = entry.unwrap();
+        match entry.file_name() {
+            b"file" => {
+                assert_eq!(entry.ino(), ino);
+                assert!(entry.file_type().map_or(true, |t| t == FileType::Regular));
+            }
+            b"subdir" => assert!(entry.file_type().map_or(true, |t| t == FileType::Directory)),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn test_dir_from_fd() {
+    let fd = openat(DirFd::Cwd, b"/".as_ref(), O_RDONLY, S_IRUSR).unwrap();
+    let mut dir = Dir::from_fd(fd).unwrap();
+    assert_eq!(dir.as_raw_fd(), fd);
+    assert!(dir.iter().any(|e| e.unwrap().file_name() == b"tmp"));
+}
+
+mod walk {
+    use nix::dir::walk::*;
+    use nix::fcntl::{open, openat, DirFd, AtFlags, O_CREAT, O_WRONLY, O_RDONLY};
+    use nix::sys::stat::{mkdirat, S_IRWXU, S_IRUSR};
+    use nix::unistd::{close, symlinkat, faccessat, F_OK};
+    use tempdir::TempDir;
+
+    // root/{file, sub/{inner, deeper/{deepest}}, link -> outside}
+    fn make_tree(name: &str) -> (TempDir, String, String) {
+        let tmp = TempDir::new(&format!("walk-{}", name));
+        let root = tmp.join("root");
+        let outside = tmp.join("outside");
+
+        mkdirat(DirFd::Cwd, outside.as_bytes(), S_IRWXU).unwrap();
+        let fd = open(format!("{}/keep", outside).as_bytes(), O_CREAT | O_WRONLY, S_IRUSR).unwrap();
+        close(fd).unwrap();
+
+        mkdirat(DirFd::Cwd, root.as_bytes(), S_IRWXU).unwrap();
+        let dirfd = open(root.as_bytes(), O_RDONLY, S_IRUSR).unwrap();
+        let d = DirFd::Fd(dirfd);
+        close(openat(d, b"file".as_ref(), O_CREAT | O_WRONLY, S_IRUSR).unwrap()).unwrap();
+        mkdirat(d, b"sub".as_ref(), S_IRWXU).unwrap();
+        close(openat(d, b"sub/inner".as_ref(), O_CREAT | O_WRONLY, S_IRUSR).unwrap()).unwrap();
+        mkdirat(d, b"sub/deeper".as_ref(), S_IRWXU).unwrap();
+        close(openat(d, b"sub/deeper/deepest".as_ref(), O_CREAT | O_WRONLY, S_IRUSR).unwrap()).unwrap();
+        symlinkat(outside.as_bytes(), d, b"link".as_ref()).unwrap();
+        close(dirfd).unwrap();
+
+        (tmp, root, outside)
+    }
+
+    fn visits(root: &str, opts: &WalkOpts) -> Vec<(Event, Vec<u8>, usize)> {
+        let mut seen = Vec::new();
+        walk_at(DirFd::Cwd, root.as_bytes(), opts, |v| {
+            seen.push((v.event, v.name.to_vec(), v.depth));
+            Ok(Control::Continue)
+        }).unwrap();
+        seen
+    }
+
+    #[test]
+    fn test_walk_orders_and_depth() {
+        let (_tmp, root, _) = make_tree("orders");
+
+        let opts = WalkOpts { pre_order: true, post_order: true, .. WalkOpts::default() };
+        let seen = visits(&root, &opts);
+        assert_eq!(seen.len(), 10);
+        assert_eq!(seen[0], (Event::PreDir, root.as_bytes().to_vec(), 0));
+        assert_eq!(seen[9], (Event::PostDir, root.as_bytes().to_vec(), 0));
+        assert!(seen.contains(&(Event::File, b"link".to_vec(), 1)));
+        assert!(seen.contains(&(Event::File, b"deepest".to_vec(), 3)));
+        assert!(!seen.iter().any(|v| v.1 == b"keep"));
+
+        let pre = seen.iter().position(|v| *v == (Event::PreDir, b"sub".to_vec(), 1)).unwrap();
+        let inner = seen.iter().position(|v| *v == (Event::File, b"inner".to_vec(), 2)).unwrap();
+        let post = seen.iter().position(|v| *v == (Event::PostDir, b"sub".to_vec(), 1)).unwrap();
+        assert!(pre < inner && inner < post);
+
+        let opts = WalkOpts { max_depth: Some(1), .. WalkOpts::default() };
+        let seen = visits(&root, &opts);
+        assert_eq!(seen.len(), 4);
+        assert!(seen.contains(&(Event::PreDir, b"sub".to_vec(), 1)));
+        assert!(!seen.iter().any(|v| v.1 == b"inner"));
+    }
+
+    #[test]
+    fn test_walk_skip_dir() {
+        let (_tmp, root, _) = make_tree("skip");
+
+        let mut seen = Vec::new();
+        walk_at(DirFd::Cwd, root.as_bytes(), &WalkOpts::default(), |v| {
+            seen.push(v.name.to_vec());
+            if v.name == b"sub" {
+                Ok(Control::SkipDir)
+            } else {
+                Ok(Control::Continue)
+            }
+        }).unwrap();
+        assert_eq!(seen.len(), 4);
+        assert!(!seen.contains(&b"inner".to_vec()));
+    }
+
+    #[test]
+    fn test_remove_dir_all_at() {
+        let (_tmp, root, outside) = make_tree("remove");
+
+        remove_dir_all_at(DirFd::Cwd, root.as_bytes()).unwrap();
+        assert!(faccessat(DirFd::Cwd, root.as_bytes(), F_OK, AtFlags::empty()).is_err());
+
+        // The symlink was removed, not followed
+        faccessat(DirFd::Cwd, format!("{}/keep", outside).as_bytes(), F_OK, AtFlags::empty()).unwrap();
+    }
+}
@@ -0,0 +1,527 @@
+use nix::errno::Errno;
+use nix::Error;
+use nix::fcntl::*;
+use nix::sys::stat::{Mode, S_IRWXU, S_IRUSR, S_IWUSR};
+use nix::unistd::{close, read, write, unlinkat};
+use libc;
+use tempdir::TempDir;
+
+fn open_dir(dir: &TempDir) -> Fd {
+    open(dir.path().as_bytes(), O_RDONLY | O_DIRECTORY, S_IRWXU).unwrap()
+}
+
+fn create(dirfd: Fd, name: &[u8], contents: &[u8]) {
+    let fd = openat(DirFd::Fd(dirfd), name, O_CREAT | O_EXCL | O_WRONLY, S_IRUSR | S_IWUSR).unwrap();
+    write(fd, contents).unwrap();
+    close(fd).unwrap();
+}
+
+fn contents(dirfd: Fd, name: &[u8]) -> Vec<u8> {
+    let fd = openat(DirFd::Fd(dirfd), name, O_RDONLY, S_IRUSR).unwrap();
+    let mut buf = [0u8; 64];
+    let len = read(fd, &mut buf).unwrap();
+    close(fd).unwrap();
+    buf[..len].to_vec()
+}
+
+#[test]
+fn test_openat() {
+    let dir = TempDir::new("openat");
+    let dirfd = open_dir(&dir);
+
+    create(dirfd, b"file", b"hello");
+    assert_eq!(contents(dirfd, b"file"), b"hello");
+
+    let res = openat(DirFd::Fd(dirfd), b"file".as_ref(), O_CREAT | O_EXCL | O_WRONLY, S_IRUSR);
+    assert_eq!(res, Err(Error::Sys(Errno::EEXIST)));
+
+    close(dirfd).unwrap();
+}
+
+#[test]
+fn test_renameat() {
+    let dir = TempDir::new("renameat");
+    let dirfd = open_dir(&dir);
+
+    create(dirfd, b"old", b"data");
+    renameat(DirFd::Fd(dirfd), b"old".as_ref(), DirFd::Fd(dirfd), b"new".as_ref()).unwrap();
+    assert_eq!(contents(dirfd, b"new"), b"data");
+
+    close(dirfd).unwrap();
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_renameat2() {
+    let dir = TempDir::new("renameat2");
+    let dirfd = open_dir(&dir);
+
+    create(dirfd, b"a", b"first");
+    create(dirfd, b"b", b"second");
+
+    let res = renameat2(DirFd::Fd(dirfd), b"a".as_ref(), DirFd::Fd(dirfd), b"b".as_ref(), RENAME_NOREPLACE);
+    assert_eq!(res, Err(Error::Sys(Errno::EEXIST)));
+
+    renameat2(DirFd::Fd(dirfd), b"a".as_ref(), DirFd::Fd(dirfd), b"b".as_ref(), RENAME_EXCHANGE).unwrap();
+    assert_eq!(contents(dirfd, b"a"), b"second");
+    assert_eq!(contents(dirfd, b"b"), b"first");
+
+    close(dirfd).unwrap();
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_openat2() {
+    let how = OpenHow::new(O_RDONLY, Mode::empty(), RESOLVE_BENEATH | RESOLVE_NO_SYMLINKS);
+    assert!(how.oflag() == O_RDONLY);
+    assert!(how.resolve() == RESOLVE_BENEATH | RESOLVE_NO_SYMLINKS);
+
+    // Skip on kernels older than 5.6
+    if openat2(DirFd::Cwd, b".".as_ref(), &how) == Err(Error::Sys(Errno::ENOSYS)) {
+        return;
+    }
+
+    let dir = TempDir::new("openat2");
+    let dirfd = open_dir(&dir);
+    create(dirfd, b"file", b"beneath");
+
+    let fd = openat2(DirFd::Fd(dirfd), b"file".as_ref(), &how).unwrap();
+    close(fd).unwrap();
+
+    assert_eq!(openat2(DirFd::Fd(dirfd), b"../etc/passwd".as_ref(), &how),
+               Err(Error::Sys(Errno::EXDEV)));
+    assert_eq!(openat2(DirFd::Fd(dirfd), b"/etc/passwd".as_ref(), &how),
+               Err(Error::Sys(Errno::EXDEV)));
+
+    close(dirfd).unwrap();
+}
+
+#[test]
+fn test_flock() {
+    use nix::errno::EWOULDBLOCK;
+
+    let dir = TempDir::new("flock");
+    let dirfd = open_dir(&dir);
+    create(dirfd, b"file", b"");
+
+    // Separate opens are separate open file descriptions
+    let fd1 = openat(DirFd::Fd(dirfd), b"file".as_ref(), O_RDWR, Mode::empty()).unwrap();
+    let fd2 = openat(DirFd::Fd(dirfd), b"file".as_ref(), O_RDWR, Mode::empty()).unwrap();
+
+    flock(fd1, LockMode::Exclusive).unwrap();
+    assert_eq!(flock(fd2, LockMode::SharedNonblock), Err(Error::Sys(EWOULDBLOCK)));
+
+    flock(fd1, LockMode::Shared).unwrap();
+    flock(fd2, LockMode::SharedNonblock).unwrap();
+    assert_eq!(flock(fd1, LockMode::ExclusiveNonblock), Err(Error::Sys(EWOULDBLOCK)));
+
+    flock(fd2, LockMode::Unlock).unwrap();
+    flock(fd1, LockMode::ExclusiveNonblock).unwrap();
+
+    close(fd1).unwrap();
+    close(fd2).unwrap();
+    close(dirfd).unwrap();
+}
+
+#[cfg(all(any(target_os = "linux", target_os = "android"), target_pointer_width = "64"))]
+#[test]
+fn test_ofd_lock() {
+    let dir = TempDir::new("ofdlock");
+    let dirfd = open_dir(&dir);
+    create(dirfd, b"file", b"");
+
+    let fd1 = openat(DirFd::Fd(dirfd), b"file".as_ref(), O_RDWR, Mode::empty()).unwrap();
+    let fd2 = openat(DirFd::Fd(dirfd), b"file".as_ref(), O_RDWR, Mode::empty()).unwrap();
+
+    {
+        let _lock = OfdLock::try_acquire(fd1, LockType::Write, 0, 10).unwrap();
+
+        assert_eq!(OfdLock::try_acquire(fd2, LockType::Read, 5, 1).err(),
+                   Some(Error::Sys(Errno::EAGAIN)));
+        assert_eq!(get_lock(fd2, LockType::Read, 0, 0).unwrap(),
+                   Some(LockConflict { lock_type: LockType::Write, start: 0, len: 10, pid: None }));
+
+        // Outside the locked range
+        OfdLock::try_acquire(fd2, LockType::Write, 10, 0).unwrap();
+    }
+
+    assert_eq!(get_lock(fd2, LockType::Write, 0, 10).unwrap(), None);
+    OfdLock::acquire(fd2, LockType::Write, 0, 10).unwrap();
+
+    close(fd1).unwrap();
+    close(fd2).unwrap();
+    close(dirfd).unwrap();
+}
+
+#[test]
+fn test_posix_lock() {
+    use nix::sys::wait::waitpid;
+    use nix::unistd::{fork, pipe};
+    use nix::unistd::Fork::*;
+
+    let dir = TempDir::new("posixlock");
+    let dirfd = open_dir(&dir);
+    create(dirfd, b"file", b"");
+    let fd = openat(DirFd::Fd(dirfd), b"file".as_ref(), O_RDWR, Mode::empty()).unwrap();
+
+    let lock = PosixLock::acquire(fd, LockType::Read, 0, 0).unwrap();
+    let (reader, writer) = pipe().unwrap();
+    let parent = unsafe { libc::getpid() };
+
+    match fork().unwrap() {
+        // A failure must not unwind into the test harness of the child
+        Child => {
+            let conflict = match get_lock(fd, LockType::Write, 100, 1) {
+                Ok(conflict) => conflict,
+                Err(_) => unsafe { libc::_exit(1) },
+            };
+            let reported = conflict == Some(LockConflict {
+                lock_type: LockType::Read, start: 0, len: 0, pid: Some(parent)
+            });
+            let denied = match PosixLock::try_acquire(fd, LockType::Write, 0, 1) {
+                Err(Error::Sys(Errno::EAGAIN)) | Err(Error::Sys(Errno::EACCES)) => true,
+                _ => false,
+            };
+            let shared = PosixLock::try_acquire(fd, LockType::Read, 0, 1).is_ok();
+
+            let res = write(writer, &[reported as u8, denied as u8, shared as u8]);
+            unsafe { libc::_exit(if res.is_ok() { 0 } else { 1 }) };
+        }
+        Parent(child_pid) => {
+            // Otherwise the read below never sees end of file
+            close(writer).unwrap();
+            waitpid(child_pid, None).unwrap();
+
+            let mut buf = [0u8; 3];
+            assert_eq!(read(reader, &mut buf).unwrap(), 3);
+            assert_eq!(buf, [1, 1, 1]);
+        }
+    }
+
+    lock.unlock().unwrap();
+    close(reader).unwrap();
+    close(fd).unwrap();
+    close(dirfd).unwrap();
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_async_io_signal() {
+    use nix::sys::signal::{sigaction, sighandler_t, SigAction, SigSet, SockFlag, SIGIO};
+    use nix::unistd::pipe;
+    use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+    use std::thread;
+    use std::time::Duration;
+
+    static SIGIO_COUNT: AtomicUsize = ATOMIC_USIZE_INIT;
+
+    extern fn on_sigio(_: libc::c_int) {
+        SIGIO_COUNT.fetch_add(1, Ordering::SeqCst);
+    }
+
+    let action = SigAction::new(on_sigio, SockFlag::empty(), SigSet::empty());
+    let old = unsafe { sigaction(SIGIO, &action).unwrap() };
+
+    let (reader, writer) = pipe().unwrap();
+    let pid = unsafe { libc::getpid() };
+
+    assert_eq!(fcntl(reader, FcntlArg::F_SETOWN(pid)), Ok(0));
+    assert_eq!(fcntl(reader, FcntlArg::F_GETOWN), Ok(pid));
+
+    fcntl(reader, FcntlArg::F_SETOWN_EX(Owner::Process(pid))).unwrap();
+    let mut owner = Owner::Thread(0);
+    fcntl(reader, FcntlArg::F_GETOWN_EX(&mut owner)).unwrap();
+    assert_eq!(owner, Owner::Process(pid));
+
+    fcntl(reader, FcntlArg::F_SETSIG(SIGIO)).unwrap();
+    assert_eq!(fcntl(reader, FcntlArg::F_GETSIG), Ok(SIGIO));
+
+    let flags = OFlag::from_bits_truncate(fcntl(reader, FcntlArg::F_GETFL).unwrap());
+    fcntl(reader, FcntlArg::F_SETFL(flags | O_ASYNC | O_NONBLOCK)).unwrap();
+    let flags = OFlag::from_bits_truncate(fcntl(reader, FcntlArg::F_GETFL).unwrap());
+    assert!(flags.contains(O_ASYNC));
+
+    write(writer, b"x").unwrap();
+
+    for _ in 0..100 {
+        if SIGIO_COUNT.load(Ordering::SeqCst) > 0 {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    assert!(SIGIO_COUNT.load(Ordering::SeqCst) > 0);
+
+    let mut buf = [0u8; 1];
+    assert_eq!(read(reader, &mut buf), Ok(1));
+
+    close(reader).unwrap();
+    close(writer).unwrap();
+    let ours = unsafe { sigaction(SIGIO, &old).unwrap() };
+    assert_eq!(ours.handler(), on_sigio as extern fn(libc::c_int) as sighandler_t);
+}
+
+#[test]
+fn test_fcntl_owner_process_group() {
+    use nix::unistd::pipe;
+
+    let (reader, writer) = pipe().unwrap();
+    let pgid = unsafe { libc::getpgrp() };
+
+    // A negative owner is a process group
+    fcntl(reader, FcntlArg::F_SETOWN(-pgid)).unwrap();
+    assert_eq!(fcntl(reader, FcntlArg::F_GETOWN), Ok(-pgid));
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        fcntl(reader, FcntlArg::F_SETOWN_EX(Owner::ProcessGroup(pgid))).unwrap();
+        let mut owner = Owner::Thread(0);
+        fcntl(reader, FcntlArg::F_GETOWN_EX(&mut owner)).unwrap();
+        assert_eq!(owner, Owner::ProcessGroup(pgid));
+        assert_eq!(fcntl(reader, FcntlArg::F_GETOWN), Ok(-pgid));
+    }
+
+    assert_eq!(fcntl(-1, FcntlArg::F_GETOWN), Err(Error::Sys(Errno::EBADF)));
+
+    close(reader).unwrap();
+    close(writer).unwrap();
+}
+
+#[test]
+fn test_fcntl_dupfd_getfd() {
+    let dir = TempDir::new("dupfd");
+    let dirfd = open_dir(&dir);
+
+    let fd = fcntl(dirfd, FcntlArg::F_DUPFD_CLOEXEC(100)).unwrap();
+    assert!(fd >= 100);
+    assert_eq!(fcntl(fd, FcntlArg::F_GETFD), Ok(FD_CLOEXEC.bits()));
+
+    fcntl(fd, FcntlArg::F_SETFD(FdFlag::empty())).unwrap();
+    assert_eq!(fcntl(fd, FcntlArg::F_GETFD), Ok(0));
+
+    close(fd).unwrap();
+    close(dirfd).unwrap();
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_fallocate_fadvise() {
+    use nix::sys::stat::fstat;
+
+    let dir = TempDir::new("fallocate");
+    let dirfd = open_dir(&dir);
+    let fd = openat(DirFd::Fd(dirfd), b"file".as_ref(), O_CREAT | O_RDWR, S_IRUSR | S_IWUSR).unwrap();
+
+    posix_fallocate(fd, 0, 8192).unwrap();
+    assert_eq!(fstat(fd).unwrap().st_size, 8192);
+
+    // Allocating past the end keeps the size, punching a hole must
+    match fallocate(fd, FALLOC_FL_KEEP_SIZE, 8192, 8192) {
+        // Not supported by every filesystem
+        Err(Error::Sys(Errno::EOPNOTSUPP)) => {}
+        res => {
+            res.unwrap();
+            assert_eq!(fstat(fd).unwrap().st_size, 8192);
+
+            fallocate(fd, FALLOC_FL_PUNCH_HOLE | FALLOC_FL_KEEP_SIZE, 0, 4096).unwrap();
+            assert_eq!(fstat(fd).unwrap().st_size, 8192);
+            assert_eq!(fallocate(fd, FALLOC_FL_PUNCH_HOLE, 0, 4096),
+                       Err(Error::Sys(Errno::EOPNOTSUPP)));
+        }
+    }
+
+    posix_fadvise(fd, 0, 0, PosixFadviseAdvice::POSIX_FADV_SEQUENTIAL).unwrap();
+    readahead(fd, 0, 8192).unwrap();
+    sync_file_range(fd, 0, 0, SYNC_FILE_RANGE_WAIT_BEFORE | SYNC_FILE_RANGE_WRITE |
+                    SYNC_FILE_RANGE_WAIT_AFTER).unwrap();
+
+    assert_eq!(posix_fadvise(-1, 0, 0, PosixFadviseAdvice::POSIX_FADV_NORMAL),
+               Err(Error::Sys(Errno::EBADF)));
+
+    close(fd).unwrap();
+    close(dirfd).unwrap();
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_splice_tee_vmsplice() {
+    use nix::sys::uio::IoVec;
+    use nix::unistd::pipe;
+
+    let dir = TempDir::new("splice");
+    let dirfd = open_dir(&dir);
+    create(dirfd, b"file", b"0123456789");
+    let fd = openat(DirFd::Fd(dirfd), b"file".as_ref(), O_RDONLY, S_IRUSR).unwrap();
+
+    let (rd1, wr1) = pipe().unwrap();
+    let (rd2, wr2) = pipe().unwrap();
+
+    // An explicit offset leaves the file offset alone
+    let mut off = 2;
+    assert_eq!(splice(fd, Some(&mut off), wr1, None, 4, SpliceFFlags::empty()).unwrap(), 4);
+    assert_eq!(off, 6);
+
+    assert_eq!(tee(rd1, wr2, 4, SpliceFFlags::empty()).unwrap(), 4);
+
+    let mut buf = [0u8; 16];
+    assert_eq!(read(rd1, &mut buf).unwrap(), 4);
+    assert_eq!(&buf[..4], b"2345");
+    assert_eq!(read(rd2, &mut buf).unwrap(), 4);
+    assert_eq!(&buf[..4], b"2345");
+
+    assert_eq!(splice(fd, None, wr1, None, 3, SpliceFFlags::empty()).unwrap(), 3);
+    assert_eq!(read(rd1, &mut buf).unwrap(), 3);
+    assert_eq!(&buf[..3], b"012");
+
+    let iov = [IoVec::from_slice(b"ab"), IoVec::from_slice(b"cd")];
+    assert_eq!(vmsplice(wr1, &iov, SpliceFFlags::empty()).unwrap(), 4);
+    assert_eq!(read(rd1, &mut buf).unwrap(), 4);
+    assert_eq!(&buf[..4], b"abcd");
+
+    for fd in [fd, rd1, wr1, rd2, wr2].iter() {
+        close(*fd).unwrap();
+    }
+    close(dirfd).unwrap();
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_copy_file_range() {
+    let dir = TempDir::new("copy_file_range");
+    let dirfd = open_dir(&dir);
+    create(dirfd, b"src", b"0123456789");
+    let src = openat(DirFd::Fd(dirfd), b"src".as_ref(), O_RDONLY, S_IRUSR).unwrap();
+    let dst = openat(DirFd::Fd(dirfd), b"dst".as_ref(), O_CREAT | O_WRONLY, S_IRUSR | S_IWUSR).unwrap();
+
+    let mut off_in = 4;
+    let mut off_out = 0;
+    assert_eq!(copy_file_range(src, Some(&mut off_in), dst, Some(&mut off_out), 6).unwrap(), 6);
+    assert_eq!((off_in, off_out), (10, 6));
+
+    assert_eq!(copy_file_range(src, None, dst, None, 2).unwrap(), 2);
+    // Without offsets, both file offsets are used, which are still at 0
+    assert_eq!(contents(dirfd, b"dst"), b"016789");
+
+    close(src).unwrap();
+    close(dst).unwrap();
+    close(dirfd).unwrap();
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_atomic_file() {
+    use nix::sys::stat::fstat;
+    use nix::unistd::fsync;
+
+    let dir = TempDir::new("atomic");
+    let dirfd = open_dir(&dir);
+
+    let mut file = AtomicFile::new(DirFd::Fd(dirfd), S_IRUSR | S_IWUSR).unwrap();
+    write(file.as_raw_fd(), b"first").unwrap();
+    fsync(file.as_raw_fd()).unwrap();
+
+    // Nothing is visible until the file is linked
+    assert_eq!(openat(DirFd::Fd(dirfd), b"file".as_ref(), O_RDONLY, S_IRUSR),
+               Err(Error::Sys(Errno::ENOENT)));
+    file.link(b"file".as_ref()).unwrap();
+    assert_eq!(contents(dirfd, b"file"), b"first");
+    assert_eq!(fstat(file.as_raw_fd()).unwrap().st_nlink, 1);
+    drop(file);
+
+    let mut file = AtomicFile::new(DirFd::Fd(dirfd), S_IRUSR | S_IWUSR).unwrap();
+    write(file.as_raw_fd(), b"second").unwrap();
+    assert_eq!(file.link(b"file".as_ref()), Err(Error::Sys(Errno::EEXIST)));
+    file.replace(b"file".as_ref()).unwrap();
+    assert_eq!(contents(dirfd, b"file"), b"second");
+    drop(file);
+
+    drop(AtomicFile::new(DirFd::Fd(dirfd), S_IRUSR | S_IWUSR).unwrap());
+
+    // Removing the directory fails if anything was left behind
+    unlinkat(DirFd::Fd(dirfd), b"file".as_ref(), AtFlags::empty()).unwrap();
+    close(dirfd).unwrap();
+    unlinkat(DirFd::Cwd, dir.path().as_bytes(), AT_REMOVEDIR).unwrap();
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_atomic_file_named() {
+    let dir = TempDir::new("atomic-named");
+    let dirfd = open_dir(&dir);
+
+    let mut file = AtomicFile::new_named(DirFd::Fd(dirfd), S_IRUSR | S_IWUSR).unwrap();
+    write(file.as_raw_fd(), b"first").unwrap();
+    file.link(b"file".as_ref()).unwrap();
+    assert_eq!(contents(dirfd, b"file"), b"first");
+    drop(file);
+
+    let mut file = AtomicFile::new_named(DirFd::Fd(dirfd), S_IRUSR | S_IWUSR).unwrap();
+    write(file.as_raw_fd(), b"second").unwrap();
+    assert_eq!(file.link(b"file".as_ref()), Err(Error::Sys(Errno::EEXIST)));
+    file.replace(b"file".as_ref()).unwrap();
+    assert_eq!(contents(dirfd, b"file"), b"second");
+    drop(file);
+
+    // Replacing also works when nothing has the name yet
+    let mut file = AtomicFile::new_named(DirFd::Fd(dirfd), S_IRUSR | S_IWUSR).unwrap();
+    write(file.as_raw_fd(), b"third").unwrap();
+    file.replace(b"other".as_ref()).unwrap();
+    assert_eq!(contents(dirfd, b"other"), b"third");
+    drop(file);
+
+    drop(AtomicFile::new_named(DirFd::Fd(dirfd), S_IRUSR | S_IWUSR).unwrap());
+
+    // Removing the directory fails if a temporary name was left behind
+    unlinkat(DirFd::Fd(dirfd), b"file".as_ref(), AtFlags::empty()).unwrap();
+    unlinkat(DirFd::Fd(dirfd), b"other".as_ref(), AtFlags::empty()).unwrap();
+    close(dirfd).unwrap();
+    unlinkat(DirFd::Cwd, dir.path().as_bytes(), AT_REMOVEDIR).unwrap();
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_file_handle() {
+    let dir = TempDir::new("handle");
+    let dirfd = open_dir(&dir);
+    create(dirfd, b"old", b"data");
+
+    let (handle, mount_id) = match name_to_handle_at(DirFd::Fd(dirfd), b"old".as_ref(), AtFlags::empty()) {
+        // The filesystem cannot encode file handles
+        Err(Error::Sys(Errno::EOPNOTSUPP)) => {
+            close(dirfd).unwrap();
+            return;
+        }
+        res => res.unwrap(),
+    };
+    assert!(handle.handle().len() > 0);
+
+    // The handle identifies the file, not its name
+    renameat(DirFd::Fd(dirfd), b"old".as_ref(), DirFd::Fd(dirfd), b"new".as_ref()).unwrap();
+    let (renamed, renamed_mount_id) = name_to_handle_at(DirFd::Fd(dirfd), b"new".as_ref(), AtFlags::empty()).unwrap();
+    assert_eq!(renamed, handle);
+    assert_eq!(renamed_mount_id, mount_id);
+
+    let stored = handle.to_bytes();
+    let restored = FileHandle::from_bytes(&stored).unwrap();
+    assert_eq!(restored, handle);
+    assert_eq!(FileHandle::from_bytes(&stored[..3]), Err(Error::Sys(Errno::EINVAL)));
+
+    match open_by_handle_at(dirfd, &restored, O_RDONLY) {
+        // Without CAP_DAC_READ_SEARCH
+        Err(Error::Sys(Errno::EPERM)) => {}
+        res => {
+            let fd = res.unwrap();
+            let mut buf = [0u8; 16];
+            assert_eq!(read(fd, &mut buf).unwrap(), 4);
+            assert_eq!(&buf[..4], b"data");
+            close(fd).unwrap();
+
+            unlinkat(DirFd::Fd(dirfd), b"new".as_ref(), AtFlags::empty()).unwrap();
+            assert_eq!(open_by_handle_at(dirfd, &restored, O_RDONLY), Err(Error::Sys(Errno::ESTALE)));
+            close(dirfd).unwrap();
+            return;
+        }
+    }
+
+    close(dirfd).unwrap();
+}
@@ -5,6 +5,7 @@ use nix::unistd::{close, unlink};
 use nix::fcntl::O_CREAT;
 use nix::sys::stat::{FileStat, S_IWUSR};
 use nix::Result;
+use tempdir::TempDir;
 
 fn assert_stat_results(stat_result: Result<FileStat>) {
     match stat_result {
@@ -39,3 +40,159 @@ fn test_stat_and_fstat() {
     close(fd).unwrap();
     unlink(filename).unwrap();
 }
+
+#[test]
+fn test_mkdirat_mkfifoat_fchmodat() {
+    use nix::fcntl::{DirFd, AtFlags, AT_REMOVEDIR, O_RDONLY, O_DIRECTORY};
+    use nix::sys::stat::{mkdirat, mkfifoat, fchmodat, S_IRWXU, S_IRUSR, S_IFIFO};
+    use nix::unistd::unlinkat;
+
+    let tmp = TempDir::new("mkdirat");
+    let dir = tmp.join("dir");
+    mkdirat(DirFd::Cwd, dir.as_bytes(), S_IRWXU).unwrap();
+    let dirfd = open(dir.as_bytes(), O_RDONLY | O_DIRECTORY, S_IRWXU).unwrap();
+
+    mkfifoat(DirFd::Fd(dirfd), b"fifo".as_ref(), S_IRWXU).unwrap();
+    fchmodat(DirFd::Fd(dirfd), b"fifo".as_ref(), S_IRUSR, AtFlags::empty()).unwrap();
+
+    let st = stat(format!("{}/fifo", dir).as_bytes()).unwrap();
+    assert_eq!(st.st_mode & 0o170000, S_IFIFO.bits());
+    assert_eq!(st.st_mode & 0o777, S_IRUSR.bits());
+
+    unlinkat(DirFd::Fd(dirfd), b"fifo".as_ref(), AtFlags::empty()).unwrap();
+    close(dirfd).unwrap();
+    unlinkat(DirFd::Cwd, dir.as_bytes(), AT_REMOVEDIR).unwrap();
+}
+
+#[test]
+fn test_lstat_fstatat_file_status() {
+    use nix::fcntl::{DirFd, AtFlags, AT_SYMLINK_NOFOLLOW, O_WRONLY};
+    use nix::sys::stat::{lstat, fstatat, FileStatus, FileType, S_IRUSR};
+    use nix::unistd::{symlinkat, write};
+
+    let dir = TempDir::new("lstat");
+    let file = dir.join("file");
+    let link = dir.join("link");
+
+    let fd = open(file.as_bytes(), O_CREAT | O_WRONLY, S_IWUSR | S_IRUSR).unwrap();
+    write(fd, b"hello").unwrap();
+    symlinkat(file.as_bytes(), DirFd::Cwd, link.as_bytes()).unwrap();
+
+    let st = FileStatus::from(lstat(link.as_bytes()).unwrap());
+    assert_eq!(st.file_type(), Some(FileType::Symlink));
+
+    let st = FileStatus::from(fstatat(DirFd::Cwd, link.as_bytes(), AT_SYMLINK_NOFOLLOW).unwrap());
+    assert_eq!(st.file_type(), Some(FileType::Symlink));
+
+    let st = FileStatus::from(fstatat(DirFd::Cwd, link.as_bytes(), AtFlags::empty()).unwrap());
+    assert_eq!(st.file_type(), Some(FileType::Regular));
+    assert!(st.mode() == S_IWUSR | S_IRUSR);
+    assert_eq!(st.size(), 5);
+    assert_eq!(st.nlink(), 1);
+    assert_eq!(st.ino(), fstat(fd).unwrap().st_ino as u64);
+    assert!(st.mtime().num_seconds() > 0);
+
+    close(fd).unwrap();
+}
+
+#[test]
+fn test_utimensat_futimens_lutimes() {
+    use nix::fcntl::{DirFd, AtFlags, AT_SYMLINK_NOFOLLOW, O_WRONLY};
+    use nix::sys::stat::{lstat, utimensat, futimens, lutimes, FileStatus, S_IRUSR};
+    use nix::sys::stat::{UTIME_NOW, UTIME_OMIT};
+    use nix::sys::time::{TimeSpec, TimeVal};
+    use nix::unistd::symlinkat;
+
+    let dir = TempDir::new("utimens");
+    let file = dir.join("file");
+    let link = dir.join("link");
+
+    let fd = open(file.as_bytes(), O_CREAT | O_WRONLY, S_IWUSR | S_IRUSR).unwrap();
+    symlinkat(file.as_bytes(), DirFd::Cwd, link.as_bytes()).unwrap();
+
+    let atime = TimeSpec { tv_sec: 1_000_000_000, tv_nsec: 123_456_789 };
+    let mtime = TimeSpec { tv_sec: 1_100_000_000, tv_nsec: 987_654_321 };
+    utimensat(DirFd::Cwd, file.as_bytes(), &atime, &mtime, AtFlags::empty()).unwrap();
+
+    let st = FileStatus::from(fstat(fd).unwrap());
+    assert_eq!(st.atime(), atime);
+    assert_eq!(st.mtime(), mtime);
+
+    // Only the access time changes
+    futimens(fd, &UTIME_NOW, &UTIME_OMIT).unwrap();
+    let st = FileStatus::from(fstat(fd).unwrap());
+    assert!(st.atime() > atime);
+    assert_eq!(st.mtime(), mtime);
+
+    // The link's times change, not the file's
+    let ltime = TimeSpec::seconds(1_200_000_000);
+    utimensat(DirFd::Cwd, link.as_bytes(), &ltime, &ltime, AT_SYMLINK_NOFOLLOW).unwrap();
+    assert_eq!(FileStatus::from(lstat(link.as_bytes()).unwrap()).mtime(), ltime);
+    assert_eq!(FileStatus::from(fstat(fd).unwrap()).mtime(), mtime);
+
+    lutimes(link.as_bytes(), &TimeVal::seconds(1_300_000_000), &TimeVal::microseconds(1_300_000_000_000_001)).unwrap();
+    assert_eq!(FileStatus::from(lstat(link.as_bytes()).unwrap()).mtime(),
+               TimeSpec { tv_sec: 1_300_000_000, tv_nsec: 1_000 });
+
+    close(fd).unwrap();
+}
+
+#[test]
+fn test_major_minor() {
+    use nix::sys::stat::{mkdev, major, minor, FileStatus, FileType};
+
+    let dev = mkdev(0x123, 0x45678);
+    assert_eq!(major(dev), 0x123);
+    assert_eq!(minor(dev), 0x45678);
+
+    // /dev/null is character device 1:3 on Linux
+    if cfg!(target_os = "linux") {
+        let st = FileStatus::from(stat(b"/dev/null".as_ref()).unwrap());
+        assert_eq!(st.file_type(), Some(FileType::CharDevice));
+        assert_eq!((major(st.rdev()), minor(st.rdev())), (1, 3));
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_statx() {
+    use nix::errno::Errno;
+    use nix::Error;
+    use nix::fcntl::{DirFd, AtFlags};
+    use nix::sys::stat::{statx, FileType, STATX_BASIC_STATS, STATX_BTIME, STATX_MNT_ID, STATX_TYPE};
+
+    let st = match statx(DirFd::Cwd, b"/dev/null".as_ref(), AtFlags::empty(), STATX_BASIC_STATS | STATX_BTIME | STATX_MNT_ID) {
+        Ok(st) => st,
+        // Kernel older than 4.11
+        Err(Error::Sys(Errno::ENOSYS)) => return,
+        Err(e) => panic!("statx failed: {:?}", e),
+    };
+
+    assert!(st.mask().contains(STATX_TYPE));
+    assert_eq!(st.file_type(), Some(FileType::CharDevice));
+    assert_eq!((st.rdev_major(), st.rdev_minor()), (1, 3));
+
+    let raw = stat(b"/dev/null".as_ref()).unwrap();
+    assert_eq!(st.ino(), raw.st_ino as u64);
+    assert_eq!(st.mtime().tv_sec, raw.st_mtime);
+    assert_eq!(st.mtime().tv_nsec, raw.st_mtime_nsec);
+    assert_eq!(st.btime().is_some(), st.mask().contains(STATX_BTIME));
+}
+
+#[test]
+fn test_chmod_fchmod() {
+    use nix::fcntl::O_RDWR;
+    use nix::sys::stat::{chmod, fchmod, S_IRUSR};
+
+    let dir = TempDir::new("chmod");
+    let file = dir.join("file");
+    let fd = open(file.as_bytes(), O_CREAT | O_RDWR, S_IWUSR).unwrap();
+
+    chmod(file.as_bytes(), S_IRUSR).unwrap();
+    assert_eq!(fstat(fd).unwrap().st_mode & 0o777, S_IRUSR.bits());
+
+    fchmod(fd, S_IRUSR | S_IWUSR).unwrap();
+    assert_eq!(stat(file.as_bytes()).unwrap().st_mode & 0o777, (S_IRUSR | S_IWUSR).bits());
+
+    close(fd).unwrap();
+}
@@ -2,6 +2,8 @@ use nix::unistd::*;
 use nix::unistd::Fork::*;
 use nix::sys::wait::*;
 use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use tempdir::TempDir;
 
 #[test]
 fn test_fork_and_waitpid() {
@@ -70,3 +72,389 @@ fn test_execve() {
         }
     }
 }
+
+#[test]
+fn test_symlinkat_readlinkat() {
+    use nix::fcntl::DirFd;
+
+    let dir = TempDir::new("symlinkat");
+    let link = dir.join("link");
+    // Longer than the initial buffer, to exercise growing it
+    let target: Vec<u8> = (0..300).map(|i| b'a' + (i % 26) as u8).collect();
+
+    symlinkat(&target[..], DirFd::Cwd, link.as_bytes()).unwrap();
+    assert_eq!(readlinkat(DirFd::Cwd, link.as_bytes()).unwrap().as_bytes(), &target[..]);
+}
+
+#[test]
+fn test_linkat_faccessat() {
+    use nix::errno::Errno;
+    use nix::Error;
+    use nix::fcntl::{open, DirFd, AtFlags, O_CREAT, O_WRONLY};
+    use nix::sys::stat::{fstat, S_IRUSR, S_IWUSR};
+
+    let dir = TempDir::new("linkat");
+    let old = dir.join("old");
+    let new = dir.join("new");
+
+    let fd = open(old.as_bytes(), O_CREAT | O_WRONLY, S_IRUSR | S_IWUSR).unwrap();
+    linkat(DirFd::Cwd, old.as_bytes(), DirFd::Cwd, new.as_bytes(), AtFlags::empty()).unwrap();
+    assert_eq!(fstat(fd).unwrap().st_nlink, 2);
+
+    faccessat(DirFd::Cwd, new.as_bytes(), R_OK | W_OK, AtFlags::empty()).unwrap();
+
+    unlinkat(DirFd::Cwd, old.as_bytes(), AtFlags::empty()).unwrap();
+    unlinkat(DirFd::Cwd, new.as_bytes(), AtFlags::empty()).unwrap();
+    assert_eq!(faccessat(DirFd::Cwd, new.as_bytes(), F_OK, AtFlags::empty()),
+               Err(Error::Sys(Errno::ENOENT)));
+    close(fd).unwrap();
+}
+
+#[test]
+fn test_truncate() {
+    use nix::fcntl::{open, O_CREAT, O_WRONLY};
+    use nix::sys::stat::{stat, S_IRUSR, S_IWUSR};
+
+    let dir = TempDir::new("truncate");
+    let path = dir.join("file");
+
+    let fd = open(path.as_bytes(), O_CREAT | O_WRONLY, S_IRUSR | S_IWUSR).unwrap();
+    write(fd, b"hello world").unwrap();
+    close(fd).unwrap();
+
+    truncate(path.as_bytes(), 5).unwrap();
+    assert_eq!(stat(path.as_bytes()).unwrap().st_size, 5);
+
+    truncate(path.as_bytes(), 100).unwrap();
+    assert_eq!(stat(path.as_bytes()).unwrap().st_size, 100);
+}
+
+#[test]
+fn test_fsync() {
+    use nix::errno::Errno;
+    use nix::Error;
+    use nix::fcntl::{open, O_CREAT, O_WRONLY};
+    use nix::sys::stat::{S_IRUSR, S_IWUSR};
+
+    let dir = TempDir::new("fsync");
+    let path = dir.join("file");
+
+    let fd = open(path.as_bytes(), O_CREAT | O_WRONLY, S_IRUSR | S_IWUSR).unwrap();
+    write(fd, b"committed").unwrap();
+    fsync(fd).unwrap();
+    sync();
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        fdatasync(fd).unwrap();
+        syncfs(fd).unwrap();
+    }
+
+    close(fd).unwrap();
+    assert_eq!(fsync(-1), Err(Error::Sys(Errno::EBADF)));
+}
+
+#[test]
+fn test_lseek_pread_pwrite() {
+    use nix::fcntl::{open, O_CREAT, O_RDWR};
+    use nix::sys::stat::{S_IRUSR, S_IWUSR};
+
+    let dir = TempDir::new("lseek");
+    let path = dir.join("file");
+    let fd = open(path.as_bytes(), O_CREAT | O_RDWR, S_IRUSR | S_IWUSR).unwrap();
+    let mut buf = [0u8; 16];
+
+    write(fd, b"0123456789").unwrap();
+    assert_eq!(lseek(fd, 0, Whence::SeekCur).unwrap(), 10);
+    assert_eq!(lseek(fd, -4, Whence::SeekEnd).unwrap(), 6);
+    assert_eq!(read(fd, &mut buf).unwrap(), 4);
+    assert_eq!(&buf[..4], b"6789");
+
+    // Neither moves the file offset
+    assert_eq!(pwrite(fd, b"ab", 2).unwrap(), 2);
+    assert_eq!(pread(fd, &mut buf[..5], 0).unwrap(), 5);
+    assert_eq!(&buf[..5], b"01ab4");
+    assert_eq!(lseek(fd, 0, Whence::SeekCur).unwrap(), 10);
+
+    assert_eq!(lseek(fd, 3, Whence::SeekSet).unwrap(), 3);
+    assert_eq!(read(fd, &mut buf[..1]).unwrap(), 1);
+    assert_eq!(buf[0], b'b');
+
+    assert_eq!(lseek(fd, -1, Whence::SeekSet), Err(nix::Error::Sys(nix::errno::Errno::EINVAL)));
+
+    close(fd).unwrap();
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_lseek_data_hole() {
+    use nix::fcntl::{open, O_CREAT, O_RDWR};
+    use nix::sys::stat::{S_IRUSR, S_IWUSR};
+
+    let dir = TempDir::new("lseek-hole");
+    let path = dir.join("file");
+    let fd = open(path.as_bytes(), O_CREAT | O_RDWR, S_IRUSR | S_IWUSR).unwrap();
+
+    // A file with a hole at the start, followed by data
+    pwrite(fd, b"data", 1 << 20).unwrap();
+    let size = (1 << 20) + 4;
+
+    let data = lseek64(fd, 0, Whence::SeekData).unwrap();
+    assert!(data <= 1 << 20);
+    assert_eq!(lseek64(fd, data, Whence::SeekHole).unwrap(), size);
+    assert_eq!(lseek64(fd, size, Whence::SeekData),
+               Err(nix::Error::Sys(nix::errno::Errno::ENXIO)));
+
+    close(fd).unwrap();
+}
+
+#[test]
+fn test_getcwd_fchdir() {
+    use nix::fcntl::{open, O_RDONLY, O_DIRECTORY};
+    use nix::sys::stat::{Mode, S_IRWXU};
+
+    let dir = TempDir::new("getcwd");
+
+    // Deep enough that the initial buffer has to grow
+    let mut path = dir.path().to_owned();
+    for _ in 0..8 {
+        path.push_str("/0123456789012345678901234567890123456789012345678901234567890123456789");
+        mkdir(path.as_bytes(), S_IRWXU).unwrap();
+    }
+
+    let fd = open(path.as_bytes(), O_RDONLY | O_DIRECTORY, Mode::empty()).unwrap();
+    let (reader, writer) = pipe().unwrap();
+
+    // Other tests use relative paths, so only the child changes directory
+    match fork().unwrap() {
+        Child => {
+            fchdir(fd).unwrap();
+            write(writer, getcwd().unwrap().as_bytes()).unwrap();
+            unsafe { ::libc::_exit(0) };
+        }
+        Parent(child_pid) => {
+            close(writer).unwrap();
+            waitpid(child_pid, None).unwrap();
+
+            let mut cwd = Vec::new();
+            let mut buf = [0u8; 1024];
+            loop {
+                match read(reader, &mut buf).unwrap() {
+                    0 => break,
+                    len => cwd.extend(buf[..len].iter().cloned()),
+                }
+            }
+            assert_eq!(cwd, path.as_bytes());
+            assert!(getcwd().unwrap().as_bytes() != path.as_bytes());
+        }
+    }
+
+    close(reader).unwrap();
+    close(fd).unwrap();
+}
+
+#[test]
+fn test_path_manipulation() {
+    use nix::errno::Errno;
+    use nix::fcntl::{open, O_CREAT, O_WRONLY};
+    use nix::sys::stat::{lstat, S_IFIFO, S_IFMT, S_IRWXU, S_IRUSR, S_IWUSR};
+    use nix::Error;
+    use std::ffi::OsStr;
+
+    let tmp = TempDir::new("paths");
+    let dir = tmp.join("dir");
+    let file = format!("{}/file", dir);
+    let renamed = format!("{}/renamed", dir);
+    let hard = format!("{}/hard", dir);
+    let soft = format!("{}/soft", dir);
+    let fifo = format!("{}/fifo", dir);
+
+    mkdir(dir.as_bytes(), S_IRWXU).unwrap();
+    assert_eq!(mkdir(dir.as_bytes(), S_IRWXU), Err(Error::Sys(Errno::EEXIST)));
+
+    let fd = open(file.as_bytes(), O_CREAT | O_WRONLY, S_IRUSR | S_IWUSR).unwrap();
+    close(fd).unwrap();
+
+    rename(file.as_bytes(), renamed.as_bytes()).unwrap();
+    link(renamed.as_bytes(), hard.as_bytes()).unwrap();
+    assert_eq!(lstat(hard.as_bytes()).unwrap().st_nlink, 2);
+
+    symlink(renamed.as_bytes(), soft.as_bytes()).unwrap();
+    assert_eq!(readlink(soft.as_bytes()).unwrap(), OsStr::from_bytes(renamed.as_bytes()));
+
+    // A target longer than the initial buffer is not truncated
+    let long = format!("{}/{}", dir, ::std::iter::repeat('x').take(1000).collect::<String>());
+    unlink(soft.as_bytes()).unwrap();
+    symlink(long.as_bytes(), soft.as_bytes()).unwrap();
+    assert_eq!(readlink(soft.as_bytes()).unwrap(), OsStr::from_bytes(long.as_bytes()));
+    assert_eq!(readlink(hard.as_bytes()), Err(Error::Sys(Errno::EINVAL)));
+
+    mkfifo(fifo.as_bytes(), S_IRUSR | S_IWUSR).unwrap();
+    assert_eq!(lstat(fifo.as_bytes()).unwrap().st_mode & S_IFMT.bits(), S_IFIFO.bits());
+
+    assert_eq!(rmdir(dir.as_bytes()), Err(Error::Sys(Errno::ENOTEMPTY)));
+    for path in [&renamed, &hard, &soft, &fifo].iter() {
+        unlink(path.as_bytes()).unwrap();
+    }
+    rmdir(dir.as_bytes()).unwrap();
+}
+
+#[test]
+fn test_chown_access() {
+    use nix::errno::Errno;
+    use nix::fcntl::{open, DirFd, O_CREAT, O_WRONLY};
+    use nix::sys::stat::{lstat, S_IRUSR, S_IWUSR};
+    use nix::Error;
+
+    let dir = TempDir::new("chown");
+    let file = dir.join("file");
+    let link = dir.join("link");
+    let fd = open(file.as_bytes(), O_CREAT | O_WRONLY, S_IRUSR | S_IWUSR).unwrap();
+    symlink(file.as_bytes(), link.as_bytes()).unwrap();
+
+    let uid = unsafe { ::libc::getuid() };
+    let gid = unsafe { ::libc::getgid() };
+
+    // Changing to the ids the file already has is always allowed
+    chown(file.as_bytes(), Some(uid), None).unwrap();
+    fchown(fd, None, Some(gid)).unwrap();
+    lchown(link.as_bytes(), Some(uid), Some(gid)).unwrap();
+    fchown(fd, None, None).unwrap();
+
+    let st = lstat(file.as_bytes()).unwrap();
+    assert_eq!((st.st_uid, st.st_gid), (uid, gid));
+
+    access(file.as_bytes(), R_OK | W_OK).unwrap();
+    faccessat(DirFd::Cwd, link.as_bytes(), F_OK, nix::fcntl::AT_EACCESS).unwrap();
+
+    unlink(file.as_bytes()).unwrap();
+    // The dangling link itself still exists
+    assert_eq!(access(link.as_bytes(), F_OK), Err(Error::Sys(Errno::ENOENT)));
+    assert_eq!(chown(link.as_bytes(), None, None), Err(Error::Sys(Errno::ENOENT)));
+    lchown(link.as_bytes(), None, None).unwrap();
+
+    close(fd).unwrap();
+}
+
+#[test]
+fn test_mkstemp_mkdtemp() {
+    use nix::errno::Errno;
+    use nix::fcntl::{fcntl, FcntlArg, FD_CLOEXEC, O_CLOEXEC};
+    use nix::sys::stat::{fstat, stat, S_IFDIR, S_IFMT};
+    use nix::Error;
+
+    let dir = TempDir::new("mkstemp");
+    let prefix = dir.join("tmp-");
+
+    let (fd, path) = mkstemp(format!("{}XXXXXX", prefix).as_bytes()).unwrap();
+    assert!(path.as_os_str().as_bytes().starts_with(prefix.as_bytes()));
+    assert!(!path.as_os_str().as_bytes().ends_with(b"XXXXXX"));
+    assert_eq!(fstat(fd).unwrap().st_mode & 0o777, 0o600);
+    write(fd, b"hello").unwrap();
+    assert_eq!(stat(path.as_os_str().as_bytes()).unwrap().st_size, 5);
+    unlink(path.as_os_str().as_bytes()).unwrap();
+    close(fd).unwrap();
+
+    let (fd, path) = mkostemp(format!("{}XXXXXX", prefix).as_bytes(), O_CLOEXEC).unwrap();
+    assert_eq!(fcntl(fd, FcntlArg::F_GETFD).unwrap(), FD_CLOEXEC.bits());
+    unlink(path.as_os_str().as_bytes()).unwrap();
+    close(fd).unwrap();
+
+    let (fd, path) = mkstemps(format!("{}XXXXXX.txt", prefix).as_bytes(), 4).unwrap();
+    assert!(path.as_os_str().as_bytes().ends_with(b".txt"));
+    unlink(path.as_os_str().as_bytes()).unwrap();
+    close(fd).unwrap();
+
+    let path = mkdtemp(format!("{}XXXXXX", prefix).as_bytes()).unwrap();
+    let st = stat(path.as_os_str().as_bytes()).unwrap();
+    assert_eq!(st.st_mode & S_IFMT.bits(), S_IFDIR.bits());
+    assert_eq!(st.st_mode & 0o777, 0o700);
+    rmdir(path.as_os_str().as_bytes()).unwrap();
+
+    // The placeholder must be exactly where it is expected
+    assert_eq!(mkstemp(format!("{}XXXXX", prefix).as_bytes()), Err(Error::Sys(Errno::EINVAL)));
+    assert_eq!(mkstemps(format!("{}XXXXXX.txt", prefix).as_bytes(), 3), Err(Error::Sys(Errno::EINVAL)));
+    assert_eq!(mkdtemp(b"XXXXX".as_ref()), Err(Error::Sys(Errno::EINVAL)));
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_pipe2_packet_mode() {
+    use nix::fcntl::{fcntl, FcntlArg, FD_CLOEXEC, O_CLOEXEC, O_DIRECT, O_NONBLOCK};
+    use nix::sys::ioctl::{ioctl, FIONREAD};
+
+    let (rd, wr) = pipe2(O_CLOEXEC | O_NONBLOCK | O_DIRECT).unwrap();
+
+    for fd in [rd, wr].iter() {
+        assert_eq!(fcntl(*fd, FcntlArg::F_GETFD).unwrap(), FD_CLOEXEC.bits());
+        assert!(fcntl(*fd, FcntlArg::F_GETFL).unwrap() & O_NONBLOCK.bits() != 0);
+    }
+    // Packet mode is a property of the write end
+    assert!(fcntl(wr, FcntlArg::F_GETFL).unwrap() & O_DIRECT.bits() != 0);
+
+    write(wr, b"abc").unwrap();
+    write(wr, b"defg").unwrap();
+
+    let mut count = 0;
+    ioctl(rd, FIONREAD(&mut count)).unwrap();
+    assert_eq!(count, 7);
+
+    // Each read returns a single packet
+    let mut buf = [0u8; 16];
+    assert_eq!(read(rd, &mut buf).unwrap(), 3);
+    assert_eq!(read(rd, &mut buf).unwrap(), 4);
+    assert_eq!(&buf[..4], b"defg");
+
+    close(rd).unwrap();
+    close(wr).unwrap();
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_pipe_size() {
+    use nix::errno::Errno;
+    use nix::Error;
+
+    let (rd, wr) = pipe().unwrap();
+
+    let size = pipe_size(rd).unwrap();
+    assert!(size > 0);
+
+    // Rounded up to a power-of-two number of pages
+    let new_size = set_pipe_size(wr, size * 2 - 1).unwrap();
+    assert_eq!(new_size, size * 2);
+    assert_eq!(pipe_size(rd).unwrap(), new_size);
+
+    assert_eq!(pipe_size(-1), Err(Error::Sys(Errno::EBADF)));
+
+    close(rd).unwrap();
+    close(wr).unwrap();
+}
+
+#[test]
+fn test_dup3() {
+    use nix::errno::Errno;
+    use nix::fcntl::{fcntl, FcntlArg, FD_CLOEXEC, O_CLOEXEC};
+    use nix::fcntl::OFlag;
+    use nix::Error;
+
+    let (rd, wr) = pipe().unwrap();
+    let (rd2, wr2) = pipe().unwrap();
+
+    assert_eq!(dup3(rd, rd2, O_CLOEXEC).unwrap(), rd2);
+    assert_eq!(fcntl(rd2, FcntlArg::F_GETFD).unwrap(), FD_CLOEXEC.bits());
+
+    assert_eq!(dup3(wr, wr2, OFlag::empty()).unwrap(), wr2);
+    assert_eq!(fcntl(wr2, FcntlArg::F_GETFD).unwrap(), 0);
+
+    // rd2 and wr2 now refer to the first pipe
+    write(wr2, b"x").unwrap();
+    let mut buf = [0u8; 1];
+    assert_eq!(read(rd2, &mut buf).unwrap(), 1);
+
+    assert_eq!(dup3(rd, rd, O_CLOEXEC), Err(Error::Sys(Errno::EINVAL)));
+
+    for fd in [rd, wr, rd2, wr2].iter() {
+        close(*fd).unwrap();
+    }
+}